@@ -1,52 +1,481 @@
-use std::{net::SocketAddr, str::FromStr};
+use std::{collections::{HashMap, HashSet}, net::SocketAddr, str::FromStr, sync::Arc, time::Duration};
 
 use axum::{
-    response::IntoResponse,
+    extract::{FromRequest, Query, Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
     routing::{get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
-use serde::{Deserialize, Serialize};
-use solana_sdk::{signature::{Keypair, Signature}, signer::Signer};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+#[cfg(feature = "rpc")]
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+#[cfg(feature = "rpc")]
+use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::{
-    instruction::{AccountMeta, Instruction},
+    derivation_path::DerivationPath,
+    signature::{Keypair, Signature},
+    signer::{keypair::keypair_from_seed_and_derivation_path, Signer},
+};
+use solana_sdk::{
+    hash::Hash,
+    ed25519_instruction::{DATA_START, PUBKEY_SERIALIZED_SIZE, SIGNATURE_SERIALIZED_SIZE},
+    instruction::{AccountMeta, CompiledInstruction, Instruction},
+    message::{v0, Message, MessageHeader, VersionedMessage},
+    packet::PACKET_DATA_SIZE,
     pubkey::Pubkey,
-    system_instruction::transfer,
+    rent::Rent,
+    sanitize::Sanitize,
+    program_pack::Pack,
+    system_instruction::{create_account, transfer},
+    transaction::{Transaction, VersionedTransaction},
+};
+use spl_token::instruction::{
+    burn_checked, close_account, initialize_mint, initialize_multisig, mint_to, set_authority, transfer as spl_transfer,
+    AuthorityType, TokenInstruction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address,
+    instruction::create_associated_token_account_idempotent,
 };
-use spl_token::instruction::{initialize_mint, mint_to, transfer as spl_transfer};
-use spl_associated_token_account::get_associated_token_address;
+use spl_token_2022::extension::{transfer_fee::TransferFee, ExtensionType};
+use spl_token_metadata_interface::state::Field;
 use base64::prelude::*;
 
 #[tokio::main]
 async fn main() {
+    tracing_subscriber::fmt::init();
+
+    // Endpoints that wait on the RPC node or a search; X-Request-Deadline applies to these
+    let long_running = Router::new()
+        .route("/send/sol", post(send_sol))
+        .route("/token/create", post(create_token))
+        .route("/keypair/vanity", post(generate_vanity_keypair))
+        .route("/send/token", post(send_token));
+    // Endpoints that exist only to query the cluster
+    #[cfg(feature = "rpc")]
+    let long_running = long_running
+        .route("/token/program-of", post(token_program_of))
+        .route("/token/decimals", post(token_decimals))
+        .route("/fee/transfer-sol", post(transfer_sol_fee))
+        .route("/token/launch/cost", post(launch_cost))
+        .route("/tx/status", post(transaction_status));
+    let long_running = long_running.route_layer(axum::middleware::from_fn(enforce_deadline));
+
+    let state = Arc::new(AppState {
+        limits: BatchLimits::from_env(),
+        ..AppState::default()
+    });
+
     let app = Router::new()
         .route("/", get(check))
+        .route("/health", get(health_live))
+        .route("/health/live", get(health_live))
+        .route("/health/ready", get(health_ready))
         .route("/keypair", post(generate_keypair))
-        .route("/token/create", post(create_token))
+        .route("/keypair/convert", post(convert_keypair))
+        .route("/keypair/from-cli-format", post(keypair_from_cli_format))
+        .route("/keypair/with-proof", post(generate_keypair_with_proof))
+        .route("/keypair/self-test", post(keypair_self_test))
+        .route("/keypair/stream", post(stream_keypairs))
+        .route("/keypair/derive-many", post(derive_many_keypairs))
+        .route("/keypair/new-with-mnemonic", post(generate_keypair_with_mnemonic))
         .route("/token/mint", post(mint_token))
+        .route("/token/mint/check-supply", post(check_mint_supply))
+        .route("/token/mint/layout", post(mint_layout))
+        .route("/token/mint/parse", post(parse_mint))
+        .route("/token/account/parse", post(parse_token_account))
+        .route("/token/burn-checked", post(burn_token_checked))
+        .route("/token/mint-close-authority/init", post(init_mint_close_authority))
+        .route("/token/close-mint", post(close_mint))
+        .route("/token/close", post(close_token_account))
+        .route("/token/metadata/init", post(init_metadata))
+        .route("/token/metadata/update", post(update_metadata_field))
+        .route("/token/ata", post(derive_ata))
+        .route("/token/ata/relationship", post(check_ata_relationship))
+        .route("/token/ata/both", post(derive_ata_both))
+        .route("/token/sweep", post(sweep_token))
+        .route("/token/consolidate", post(consolidate_tokens))
+        .route("/token/native-mint", get(native_mint))
+        .route("/token/wsol/ensure", post(ensure_wsol_account))
+        .route("/token/set-authority", post(set_token_authority))
+        .route("/token/instruction/decode", post(decode_token_instruction))
+        .route("/token/multisig/create/full", post(create_multisig_full))
+        .route("/token/transfer-checked-with-fee", post(transfer_checked_with_fee))
+        .route("/token/transfer-fee/withdraw", post(withdraw_withheld_fees))
         .route("/message/sign", post(sign_message))
+        .route("/message/sign/onchain", post(sign_message_onchain))
+        .route("/message/sign-and-instruction", post(sign_message_onchain))
+        .route("/message/sign-tx-message", post(sign_tx_message))
+        .route("/message/sign/siws", post(sign_siws_message))
+        .route("/message/verify/siws", post(verify_siws_message))
         .route("/message/verify", post(verify_message))
-        .route("/send/sol", post(send_sol))
-        .route("/send/token", post(send_token));
+        .route("/message/verify/one-signer", post(verify_one_signer))
+        .route("/message/verify/hash", post(verify_hash_signature))
+        .route("/message/verify/canonical", post(verify_canonical_signature))
+        .route("/send/sol/safe-check", post(send_sol_safe_check))
+        .route("/send/token/as-delegate", post(send_token_as_delegate))
+        .route("/send/token/plan", post(plan_token_transfers))
+        .route("/auth/token", post(issue_auth_token))
+        .route("/auth/verify", post(verify_auth_token_handler))
+        .route("/attestation/sign", post(sign_attestation))
+        .route("/attestation/verify", post(verify_attestation))
+        .route("/util/qr", post(generate_qr))
+        .route("/util/identicon", post(generate_identicon))
+        .route("/util/base58/normalize", post(normalize_base58))
+        .route("/util/derivation-path/validate", post(validate_derivation_path))
+        .route("/tx/sign-partial", post(sign_partial_transaction))
+        .route("/tx/signing-status", post(signing_status))
+        .route("/tx/upgrade-v0", post(upgrade_to_v0))
+        .route("/tx/size", post(transaction_size))
+        .route("/tx/decode", post(decode_transaction_json))
+        .route("/tx/memo", post(build_memo_transaction))
+        .route("/tx/preview", post(preview_transaction))
+        .route("/tx/build/raw", post(build_raw_transaction))
+        .route("/tx/sign-multi", post(sign_multi_transaction))
+        .route("/tx/set-fee-payer", post(set_fee_payer))
+        .route("/instruction/combined/decode", post(decode_combined_instructions))
+        .route("/instruction/encode", post(encode_instruction))
+        .route("/instruction/validate", post(validate_instruction))
+        .route("/instruction/diff", post(diff_instructions))
+        .route("/instruction/required-signers", post(required_signers))
+        .route("/rent/mint", post(mint_rent))
+        .route("/pda/derive/batch", post(derive_pda_batch))
+        .route("/pda/create", post(create_pda))
+        .merge(long_running)
+        .with_state(Arc::clone(&state));
 
     let port: u16 = std::env::var("PORT")
         .unwrap_or_else(|_| "3000".to_string())
         .parse()
         .expect("PORT must be a number");
 
+    let keepalive_secs: u64 = std::env::var("TCP_KEEPALIVE_SECS")
+        .unwrap_or_else(|_| "60".to_string())
+        .parse()
+        .expect("TCP_KEEPALIVE_SECS must be a number");
+
+    let max_connections: usize = std::env::var("MAX_CONNECTIONS")
+        .unwrap_or_else(|_| "1024".to_string())
+        .parse()
+        .expect("MAX_CONNECTIONS must be a number");
+
+    // Read lazily by handlers; touched here so a bad value fails at startup
+    server_max_amount();
+    server_signing_allowed();
+    #[cfg(feature = "rpc")]
+    configured_rpc_url();
+
+    let require_auth: bool = std::env::var("REQUIRE_AUTH")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .expect("REQUIRE_AUTH must be true or false");
+
+    let app = if require_auth {
+        let authorized: HashSet<Pubkey> = std::env::var("AUTHORIZED_PUBKEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| Pubkey::from_str(key).expect("AUTHORIZED_PUBKEYS must be valid pubkeys"))
+            .collect();
+        assert!(
+            !authorized.is_empty(),
+            "AUTHORIZED_PUBKEYS must list at least one pubkey when REQUIRE_AUTH is on"
+        );
+        app.layer(axum::middleware::from_fn_with_state(Arc::new(authorized), require_bearer_auth))
+    } else {
+        app
+    };
+
+    let enable_compression: bool = std::env::var("ENABLE_COMPRESSION")
+        .unwrap_or_else(|_| "true".to_string())
+        .parse()
+        .expect("ENABLE_COMPRESSION must be true or false");
+
+    // Only applies when the client sends a matching Accept-Encoding
+    let app = if enable_compression {
+        app.layer(tower_http::compression::CompressionLayer::new())
+    } else {
+        app
+    };
+
+    // A panicking handler still answers with a structured 500 instead of dropping the connection
+    let app = app.layer(tower_http::catch_panic::CatchPanicLayer::custom(handle_panic));
+
+    let app = app.layer(axum::middleware::from_fn_with_state(state, count_requests));
+
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
 
     let listener = tokio::net::TcpListener::bind(addr)
         .await
         .expect("Failed to bind");
 
+    let keepalive = socket2::TcpKeepalive::new().with_time(Duration::from_secs(keepalive_secs));
+
     println!("Server running on http://0.0.0.0:{}", port);
-    axum::serve(listener, app).await.expect("Server failed");
+    serve_with_connection_limit(listener, app, max_connections, keepalive).await;
+}
+
+/// Accepts connections only while fewer than `max_connections` are open, so
+/// idle keep-alive clients count against the limit too. Further clients wait
+/// in the listen backlog until a connection closes. TCP keep-alive is set on
+/// every accepted socket, since not all platforms inherit it from the listener.
+async fn serve_with_connection_limit(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    max_connections: usize,
+    keepalive: socket2::TcpKeepalive,
+) {
+    let slots = Arc::new(tokio::sync::Semaphore::new(max_connections));
+
+    loop {
+        let permit = Arc::clone(&slots)
+            .acquire_owned()
+            .await
+            .expect("connection semaphore is never closed");
+
+        let socket = match listener.accept().await {
+            Ok((socket, _)) => socket,
+            Err(e) => {
+                // Usually out of file descriptors; back off instead of spinning
+                tracing::warn!(error = %e, "failed to accept connection");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+
+        if let Err(e) = socket2::SockRef::from(&socket).set_tcp_keepalive(&keepalive) {
+            tracing::warn!(error = %e, "failed to set TCP keep-alive");
+        }
+
+        let service = hyper_util::service::TowerToHyperService::new(app.clone());
+        tokio::spawn(async move {
+            let connection = hyper::server::conn::http1::Builder::new()
+                .serve_connection(hyper_util::rt::TokioIo::new(socket), service)
+                .with_upgrades();
+            if let Err(e) = connection.await {
+                tracing::debug!(error = %e, "connection closed with error");
+            }
+            // The slot frees up only once the client is fully gone
+            drop(permit);
+        });
+    }
 }
 
 async fn check() -> &'static str {
     "Hello World"
 }
 
+/// Logs the panic in full but only tells the client that something went wrong.
+fn handle_panic(panic: Box<dyn std::any::Any + Send + 'static>) -> Response {
+    let message = panic
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| panic.downcast_ref::<&str>().copied())
+        .unwrap_or("non-string panic payload");
+    tracing::error!(panic = message, "handler panicked");
+
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ApiResponse::<()>::from(Err("internal error".to_string()))),
+    )
+        .into_response()
+}
+
+// ===== HEALTH CHECKS =====
+
+/// Shared server state, handed to handlers and middleware via `State`.
+#[derive(Default)]
+struct AppState {
+    requests_served: std::sync::atomic::AtomicU64,
+    limits: BatchLimits,
+}
+
+/// Per-request caps for the batch endpoints. The error for an oversized batch
+/// always quotes the configured value.
+#[derive(Clone, Copy)]
+struct BatchLimits {
+    pdas: usize,
+    withdraw_sources: usize,
+    derive_keypairs: usize,
+    verify_items: usize,
+    consolidate_sources: usize,
+    plan_transfers: usize,
+    stream_keypairs: usize,
+}
+
+impl Default for BatchLimits {
+    fn default() -> Self {
+        BatchLimits {
+            pdas: DEFAULT_MAX_BATCH_PDAS,
+            withdraw_sources: MAX_WITHDRAW_SOURCES,
+            derive_keypairs: DEFAULT_MAX_DERIVE_COUNT,
+            verify_items: DEFAULT_MAX_ONE_SIGNER_ITEMS,
+            consolidate_sources: MAX_CONSOLIDATE_SOURCES,
+            plan_transfers: DEFAULT_MAX_PLAN_TRANSFERS,
+            stream_keypairs: DEFAULT_MAX_STREAM_KEYPAIRS,
+        }
+    }
+}
+
+impl BatchLimits {
+    /// Reads the `MAX_BATCH_*` overrides. Caps that come from the transaction
+    /// size limit can only be lowered, since a larger batch wouldn't fit.
+    fn from_env() -> Self {
+        fn read(name: &str, default: usize, ceiling: usize) -> usize {
+            let value = std::env::var(name)
+                .map(|value| value.parse().unwrap_or_else(|_| panic!("{name} must be a number")))
+                .unwrap_or(default);
+            assert!((1..=ceiling).contains(&value), "{name} must be between 1 and {ceiling}");
+            value
+        }
+
+        let defaults = BatchLimits::default();
+        BatchLimits {
+            pdas: read("MAX_BATCH_PDAS", defaults.pdas, usize::MAX),
+            withdraw_sources: read("MAX_BATCH_WITHDRAW_SOURCES", defaults.withdraw_sources, MAX_WITHDRAW_SOURCES),
+            derive_keypairs: read("MAX_BATCH_DERIVE_KEYPAIRS", defaults.derive_keypairs, u32::MAX as usize),
+            verify_items: read("MAX_BATCH_VERIFY_ITEMS", defaults.verify_items, usize::MAX),
+            consolidate_sources: read(
+                "MAX_BATCH_CONSOLIDATE_SOURCES",
+                defaults.consolidate_sources,
+                MAX_CONSOLIDATE_SOURCES,
+            ),
+            plan_transfers: read("MAX_BATCH_PLAN_TRANSFERS", defaults.plan_transfers, usize::MAX),
+            stream_keypairs: read("MAX_BATCH_STREAM_KEYPAIRS", defaults.stream_keypairs, usize::MAX),
+        }
+    }
+}
+
+/// Counts every request that reaches the router, including rejected ones.
+async fn count_requests(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    state
+        .requests_served
+        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    next.run(request).await
+}
+
+#[cfg(feature = "rpc")]
+/// Public RPC endpoint for a cluster name.
+fn cluster_rpc_url(cluster: &str) -> Result<&'static str, String> {
+    match cluster.trim() {
+        "mainnet" | "mainnet-beta" => Ok("https://api.mainnet-beta.solana.com"),
+        "devnet" => Ok("https://api.devnet.solana.com"),
+        "testnet" => Ok("https://api.testnet.solana.com"),
+        "localnet" => Ok("http://127.0.0.1:8899"),
+        other => Err(format!(
+            "Unknown cluster: {other}, expected mainnet, devnet, testnet or localnet"
+        )),
+    }
+}
+
+#[cfg(feature = "rpc")]
+/// RPC endpoint configured for the server through `RPC_URL` or `RPC_CLUSTER`, if any.
+fn configured_rpc_url() -> Option<String> {
+    let non_empty = |name| {
+        std::env::var(name)
+            .ok()
+            .filter(|value: &String| !value.trim().is_empty())
+    };
+
+    match (non_empty("RPC_URL"), non_empty("RPC_CLUSTER")) {
+        (Some(_), Some(_)) => panic!("Set either RPC_URL or RPC_CLUSTER, not both"),
+        (Some(url), None) => Some(url),
+        (None, Some(cluster)) => Some(
+            cluster_rpc_url(&cluster)
+                .expect("RPC_CLUSTER must be mainnet, devnet, testnet or localnet")
+                .to_string(),
+        ),
+        (None, None) => None,
+    }
+}
+
+#[cfg(feature = "rpc")]
+/// Client for the request's `rpc_url` or `cluster` (mainnet, devnet, testnet or
+/// localnet), falling back to the server's `RPC_URL`/`RPC_CLUSTER`.
+fn rpc_client(rpc_url: Option<&str>, cluster: Option<&str>) -> Result<RpcClient, String> {
+    let rpc_url = rpc_url.map(str::trim).filter(|url| !url.is_empty());
+    let cluster = cluster.map(str::trim).filter(|cluster| !cluster.is_empty());
+
+    let url = match (rpc_url, cluster) {
+        (Some(_), Some(_)) => return Err("Pass either rpc_url or cluster, not both".to_string()),
+        (Some(url), None) => url.to_string(),
+        (None, Some(cluster)) => cluster_rpc_url(cluster)?.to_string(),
+        (None, None) => configured_rpc_url().ok_or("No RPC endpoint: pass rpc_url or cluster, or set RPC_URL")?,
+    };
+
+    Ok(RpcClient::new_with_timeout(url, Duration::from_secs(10)))
+}
+
+#[derive(Serialize)]
+struct HealthResponse {
+    status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rpc: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requests_served: Option<u64>,
+}
+
+/// Liveness: answers as long as the process is running.
+async fn health_live(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let response = HealthResponse {
+        status: "ok".to_string(),
+        rpc: None,
+        requests_served: Some(state.requests_served.load(std::sync::atomic::Ordering::Relaxed)),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+/// Readiness: additionally requires the configured RPC endpoint to report healthy.
+#[cfg(feature = "rpc")]
+async fn health_ready() -> impl IntoResponse {
+    let Some(rpc_url) = configured_rpc_url() else {
+        let response = HealthResponse {
+            status: "ok".to_string(),
+            rpc: Some("not configured".to_string()),
+            requests_served: None,
+        };
+        return (StatusCode::OK, Json(ApiResponse::from(Ok(response))));
+    };
+
+    let client = RpcClient::new_with_timeout(rpc_url, Duration::from_secs(2));
+    match client.get_health().await {
+        Ok(()) => {
+            let response = HealthResponse {
+                status: "ok".to_string(),
+                rpc: Some("ok".to_string()),
+                requests_served: None,
+            };
+            (StatusCode::OK, Json(ApiResponse::from(Ok(response))))
+        }
+        Err(e) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse::from(Err(format!("RPC endpoint not ready: {e}")))),
+        ),
+    }
+}
+
+/// Readiness without the `rpc` feature: there is no RPC endpoint to wait on.
+#[cfg(not(feature = "rpc"))]
+async fn health_ready() -> impl IntoResponse {
+    let response = HealthResponse {
+        status: "ok".to_string(),
+        rpc: Some("disabled".to_string()),
+        requests_served: None,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+/// Error for RPC-backed options on a server built without the `rpc` feature.
+#[cfg(not(feature = "rpc"))]
+const RPC_DISABLED: &str = "This server was built without RPC support";
+
 // ===== COMMON RESPONSE STRUCTURES =====
 
 #[derive(Serialize)]
@@ -54,6 +483,70 @@ async fn check() -> &'static str {
 enum ApiResponse<T> {
     Success { success: bool, data: T },
     Error { success: bool, error: String },
+    Invalid { success: bool, error: String, errors: Vec<FieldError> },
+}
+
+#[derive(Serialize)]
+struct FieldError {
+    field: String,
+    message: String,
+}
+
+impl FieldError {
+    fn new(field: &str, message: &str) -> Self {
+        FieldError {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl<T> ApiResponse<T> {
+    /// Reports every failed field at once instead of stopping at the first one.
+    fn invalid(errors: Vec<FieldError>) -> Self {
+        ApiResponse::Invalid {
+            success: false,
+            error: "Validation failed".to_string(),
+            errors,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ValidationParams {
+    #[serde(default)]
+    validate_all: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChecksumParams {
+    #[serde(default)]
+    checksum: bool,
+}
+
+/// Length and hex SHA-256 of the raw instruction data, so clients can assert
+/// that the data they forward is exactly what was built.
+#[derive(Serialize)]
+struct DataChecksum {
+    instruction_data_len: usize,
+    instruction_data_sha256: String,
+}
+
+#[derive(Serialize)]
+struct WithChecksum<T> {
+    #[serde(flatten)]
+    response: T,
+    #[serde(flatten)]
+    checksum: Option<DataChecksum>,
+}
+
+fn with_checksum<T>(response: T, data: &[u8], enabled: bool) -> WithChecksum<T> {
+    let checksum = enabled.then(|| DataChecksum {
+        instruction_data_len: data.len(),
+        instruction_data_sha256: hex::encode(Sha256::digest(data)),
+    });
+
+    WithChecksum { response, checksum }
 }
 
 impl<T> From<Result<T, String>> for ApiResponse<T> {
@@ -71,173 +564,603 @@ impl<T> From<Result<T, String>> for ApiResponse<T> {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct AccountMetaJson {
     pubkey: String,
     is_signer: bool,
     is_writable: bool,
+    // Output-only hints for accounts that must be passed but are not user accounts
+    #[serde(default, deserialize_with = "discard_output_only")]
+    is_sysvar: bool,
+    #[serde(default, deserialize_with = "discard_output_only")]
+    is_program: bool,
 }
 
-// ===== 1. GENERATE KEYPAIR ENDPOINT =====
-
-#[derive(Serialize)]
-struct KeypairResponse {
-    pubkey: String,
-    secret: String,
+/// Accepts and drops an output-only field, so instruction JSON can be sent back
+/// as returned, even under `?strict_fields=true`.
+fn discard_output_only<'de, D: Deserializer<'de>, T: Default>(deserializer: D) -> Result<T, D::Error> {
+    Value::deserialize(deserializer)?;
+    Ok(T::default())
 }
 
-async fn generate_keypair() -> impl IntoResponse {
-    let keypair = Keypair::new();
-    let pubkey = keypair.pubkey().to_string();
-    let secret_bytes = keypair.to_bytes();
-    let secret = bs58::encode(secret_bytes).into_string();
-
-    let response = KeypairResponse { pubkey, secret };
-
-    Json(ApiResponse::from(Ok(response)))
+impl From<&AccountMeta> for AccountMetaJson {
+    fn from(meta: &AccountMeta) -> Self {
+        AccountMetaJson {
+            pubkey: meta.pubkey.to_string(),
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+            is_sysvar: solana_sdk::sysvar::is_sysvar_id(&meta.pubkey),
+            is_program: is_program_id(&meta.pubkey),
+        }
+    }
 }
 
-// ===== 2. CREATE TOKEN ENDPOINT =====
-
-#[derive(Debug, Deserialize)]
-struct TokenCreateRequest {
-    #[serde(rename = "mintAuthority")]
-    mint_authority: String,
-    mint: String,
-    decimals: u8,
+/// Programs that show up as plain accounts in some instructions, e.g. the system
+/// and token programs in `create_associated_token_account`.
+fn is_program_id(id: &Pubkey) -> bool {
+    known_program_name(id).is_some()
+        || [
+            solana_sdk::bpf_loader::id(),
+            solana_sdk::bpf_loader_deprecated::id(),
+            solana_sdk::bpf_loader_upgradeable::id(),
+            solana_sdk::compute_budget::id(),
+            solana_sdk::ed25519_program::id(),
+            solana_sdk::secp256k1_program::id(),
+            solana_sdk::address_lookup_table::program::id(),
+        ]
+        .contains(id)
 }
 
-#[derive(Serialize)]
-struct TokenCreateResponse {
+/// Wire format for a single instruction, used both in responses and as input.
+#[derive(Serialize, Deserialize)]
+struct InstructionJson {
     program_id: String,
     accounts: Vec<AccountMetaJson>,
+    #[serde(alias = "data")]
     instruction_data: String,
+    // Output-only copy of instruction_data for hex-oriented debugging tools
+    #[serde(default, deserialize_with = "discard_output_only")]
+    instruction_data_hex: String,
+    #[serde(default, deserialize_with = "discard_output_only")]
+    related_programs: Vec<RelatedProgram>,
 }
 
-async fn create_token(Json(req): Json<TokenCreateRequest>) -> impl IntoResponse {
-    let mint = match Pubkey::from_str(&req.mint) {
-        Ok(p) => p,
-        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
-    };
+#[derive(Serialize, Deserialize)]
+struct RelatedProgram {
+    name: String,
+    program_id: String,
+}
 
-    let mint_authority = match Pubkey::from_str(&req.mint_authority) {
-        Ok(p) => p,
-        Err(_) => return Json(ApiResponse::from(Err("Invalid mint_authority pubkey".into()))),
-    };
+fn known_program_name(program_id: &Pubkey) -> Option<&'static str> {
+    if *program_id == solana_sdk::system_program::id() {
+        Some("system")
+    } else if *program_id == spl_token::id() {
+        Some("spl-token")
+    } else if *program_id == spl_token_2022::id() {
+        Some("token-2022")
+    } else if *program_id == spl_associated_token_account::id() {
+        Some("associated-token")
+    } else if *program_id == spl_memo::id() {
+        Some("memo")
+    } else {
+        None
+    }
+}
 
-    let freeze_authority = None;
+/// Well-known programs an instruction touches (its own program plus any program
+/// passed as an account), followed by `extra` programs the endpoint relied on,
+/// e.g. the associated token program for derived ATAs.
+fn related_programs(instruction: &Instruction, extra: &[Pubkey]) -> Vec<RelatedProgram> {
+    let candidates = std::iter::once(&instruction.program_id)
+        .chain(instruction.accounts.iter().map(|meta| &meta.pubkey))
+        .chain(extra);
 
-    let ix = initialize_mint(
-        &spl_token::id(),
-        &mint,
-        &mint_authority,
-        freeze_authority.as_ref(),
-        req.decimals,
-    );
+    let mut seen = HashSet::new();
+    candidates
+        .filter(|id| seen.insert(**id))
+        .filter_map(|id| {
+            known_program_name(id).map(|name| RelatedProgram {
+                name: name.to_string(),
+                program_id: id.to_string(),
+            })
+        })
+        .collect()
+}
 
-    if let Err(e) = ix {
-        return Json(ApiResponse::from(Err(format!("Failed to build instruction: {e}"))));
+impl From<Instruction> for InstructionJson {
+    fn from(instruction: Instruction) -> Self {
+        let accounts = instruction
+            .accounts
+            .iter()
+            .map(AccountMetaJson::from)
+            .collect();
+
+        InstructionJson {
+            program_id: instruction.program_id.to_string(),
+            related_programs: related_programs(&instruction, &[]),
+            accounts,
+            instruction_data_hex: hex::encode(&instruction.data),
+            instruction_data: base64::prelude::BASE64_STANDARD.encode(instruction.data),
+        }
     }
+}
 
-    let instruction = ix.unwrap();
+#[derive(Debug, Deserialize)]
+struct AmountGuardParams {
+    max_amount: Option<u64>,
+}
 
-    let accounts: Vec<AccountMetaJson> = instruction
-        .accounts
-        .iter()
-        .map(|meta| AccountMetaJson {
-            pubkey: meta.pubkey.to_string(),
-            is_signer: meta.is_signer,
-            is_writable: meta.is_writable,
-        })
-        .collect();
+/// Server-wide amount cap from `MAX_AMOUNT`; unlimited when unset.
+fn server_max_amount() -> Option<u64> {
+    static LIMIT: std::sync::OnceLock<Option<u64>> = std::sync::OnceLock::new();
+    *LIMIT.get_or_init(|| {
+        std::env::var("MAX_AMOUNT")
+            .ok()
+            .map(|value| value.parse().expect("MAX_AMOUNT must be a number"))
+    })
+}
 
-    let instruction_data = base64::prelude::BASE64_STANDARD.encode(instruction.data.clone());
+impl AmountGuardParams {
+    /// Rejects amounts above `?max_amount` or `MAX_AMOUNT` (whichever is lower),
+    /// which usually means the caller mixed up units, e.g. SOL and lamports.
+    fn check(&self, amount: u64) -> Result<(), String> {
+        let limit = match (self.max_amount, server_max_amount()) {
+            (Some(request), Some(server)) => Some(request.min(server)),
+            (request, server) => request.or(server),
+        };
 
-    let response = TokenCreateResponse {
-        program_id: instruction.program_id.to_string(),
-        accounts,
-        instruction_data,
-    };
+        match limit {
+            Some(max) if amount > max => Err(format!(
+                "Amount {amount} exceeds the maximum of {max}; check the units (lamports vs SOL, raw vs UI amount)"
+            )),
+            _ => Ok(()),
+        }
+    }
+}
 
-    Json(ApiResponse::from(Ok(response)))
+#[derive(Debug, Deserialize)]
+struct EchoParams {
+    #[serde(default = "default_echo_message")]
+    echo_message: bool,
 }
 
-// ===== 3. MINT TOKEN ENDPOINT =====
+fn default_echo_message() -> bool {
+    true
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum BigintFormat {
+    #[default]
+    Number,
+    String,
+}
 
 #[derive(Debug, Deserialize)]
-struct MintTokenRequest {
-    mint: String,
-    destination: String,
-    authority: String,
-    amount: u64,
+struct BigintParams {
+    #[serde(default)]
+    bigint: BigintFormat,
 }
 
-async fn mint_token(Json(req): Json<MintTokenRequest>) -> impl IntoResponse {
-    let mint = match Pubkey::from_str(&req.mint) {
-        Ok(p) => p,
-        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
-    };
+impl BigintParams {
+    fn wrap(&self, value: u64) -> BigUint {
+        BigUint {
+            value,
+            as_string: self.bigint == BigintFormat::String,
+        }
+    }
+}
 
-    let destination = match Pubkey::from_str(&req.destination) {
-        Ok(p) => p,
-        Err(_) => return Json(ApiResponse::from(Err("Invalid destination pubkey".into()))),
-    };
+/// u64 output that serializes as a JSON string under `?bigint=string`, since JS
+/// numbers silently round anything above 2^53.
+#[derive(Debug, Clone, Copy)]
+struct BigUint {
+    value: u64,
+    as_string: bool,
+}
 
-    let authority = match Pubkey::from_str(&req.authority) {
-        Ok(p) => p,
-        Err(_) => return Json(ApiResponse::from(Err("Invalid authority pubkey".into()))),
-    };
+impl Serialize for BigUint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.as_string {
+            serializer.collect_str(&self.value)
+        } else {
+            serializer.serialize_u64(self.value)
+        }
+    }
+}
 
-    let instruction_result = mint_to(
-        &spl_token::id(),
-        &mint,
-        &destination,
-        &authority,
-        &[], // no multisig signers
-        req.amount,
-    );
+#[derive(Debug, Deserialize)]
+struct CombinedParams {
+    #[serde(default)]
+    combined: bool,
+}
 
-    if let Err(e) = instruction_result {
-        return Json(ApiResponse::from(Err(format!("Failed to build mint instruction: {}", e))));
+/// Packs instructions into one opaque blob: for each instruction, a u32
+/// little-endian byte length followed by the bincode-serialized `Instruction`.
+/// The result is base64-encoded; `/instruction/combined/decode` reverses it.
+fn combine_instructions(instructions: &[Instruction]) -> Result<String, String> {
+    let mut combined = Vec::new();
+    for instruction in instructions {
+        let bytes = bincode::serialize(instruction)
+            .map_err(|e| format!("Failed to serialize instruction: {e}"))?;
+        combined.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        combined.extend_from_slice(&bytes);
     }
 
-    let instruction = instruction_result.unwrap();
+    Ok(base64::prelude::BASE64_STANDARD.encode(combined))
+}
 
-    let accounts: Vec<AccountMetaJson> = instruction
-        .accounts
-        .iter()
-        .map(|meta| AccountMetaJson {
-            pubkey: meta.pubkey.to_string(),
-            is_signer: meta.is_signer,
-            is_writable: meta.is_writable,
-        })
-        .collect();
+fn split_combined_instructions(encoded: &str) -> Result<Vec<Instruction>, String> {
+    let bytes = base64::prelude::BASE64_STANDARD
+        .decode(encoded)
+        .map_err(|_| "Invalid base64 combined instructions".to_string())?;
 
-    let response = TokenCreateResponse {
-        program_id: instruction.program_id.to_string(),
-        accounts,
-        instruction_data: base64::prelude::BASE64_STANDARD.encode(instruction.data.clone()),
-    };
+    let mut instructions = Vec::new();
+    let mut rest = bytes.as_slice();
+    while !rest.is_empty() {
+        let index = instructions.len();
+        let (len, tail) = rest
+            .split_first_chunk::<4>()
+            .ok_or_else(|| format!("Truncated length prefix at instruction {index}"))?;
+        let len = u32::from_le_bytes(*len) as usize;
+        if tail.len() < len {
+            return Err(format!("Truncated data at instruction {index}"));
+        }
 
-    Json(ApiResponse::from(Ok(response)))
+        let (data, tail) = tail.split_at(len);
+        let instruction = bincode::deserialize(data)
+            .map_err(|_| format!("Failed to deserialize instruction {index}"))?;
+        instructions.push(instruction);
+        rest = tail;
+    }
+
+    Ok(instructions)
 }
 
-// ===== 4. SIGN MESSAGE ENDPOINT =====
+impl InstructionJson {
+    fn to_instruction(&self) -> Result<Instruction, String> {
+        let program_id =
+            Pubkey::from_str(&self.program_id).map_err(|_| "Invalid program_id".to_string())?;
 
-#[derive(Debug, Deserialize)]
-struct SignMessageRequest {
-    message: String,
-    secret: String,
-}
+        let mut accounts = Vec::with_capacity(self.accounts.len());
+        for (i, meta) in self.accounts.iter().enumerate() {
+            let pubkey = Pubkey::from_str(&meta.pubkey)
+                .map_err(|_| format!("Invalid account pubkey at index {i}"))?;
+            accounts.push(AccountMeta {
+                pubkey,
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            });
+        }
+
+        let data = base64::prelude::BASE64_STANDARD
+            .decode(&self.instruction_data)
+            .map_err(|_| "Invalid base64 instruction_data".to_string())?;
+
+        Ok(Instruction { program_id, accounts, data })
+    }
+}
+
+/// Decodes a 64-byte secret key into a keypair. Accepts base58, or the JSON
+/// byte array stored in Solana CLI keyfiles (`~/.config/solana/id.json`).
+fn parse_keypair(secret: &str) -> Result<Keypair, String> {
+    let secret = secret.trim();
+    let decoded = if secret.starts_with('[') {
+        serde_json::from_str::<Vec<u8>>(secret).ok()
+    } else {
+        bs58::decode(secret).into_vec().ok()
+    };
+
+    let secret_bytes = match decoded {
+        Some(bytes) if bytes.len() == 64 => bytes,
+        _ => return Err("Invalid secret key".to_string()),
+    };
+
+    Keypair::from_bytes(&secret_bytes).map_err(|_| "Failed to construct keypair".to_string())
+}
+
+/// Decodes a base64, bincode-serialized legacy transaction and checks that
+/// its signature slots line up with the message header.
+fn decode_transaction(encoded: &str) -> Result<Transaction, String> {
+    let bytes = base64::prelude::BASE64_STANDARD
+        .decode(encoded)
+        .map_err(|_| "Invalid base64 transaction".to_string())?;
+
+    let tx: Transaction =
+        bincode::deserialize(&bytes).map_err(|_| "Failed to deserialize transaction".to_string())?;
+
+    if tx.sanitize().is_err()
+        || tx.signatures.len() != tx.message.header.num_required_signatures as usize
+    {
+        return Err("Malformed transaction".to_string());
+    }
+
+    Ok(tx)
+}
+
+fn encode_transaction(tx: &Transaction, encoding: TxEncoding) -> Result<String, String> {
+    bincode::serialize(tx)
+        .map(|bytes| encoding.encode(&bytes))
+        .map_err(|e| format!("Failed to serialize transaction: {e}"))
+}
+
+/// Encoding for serialized transactions in responses. base58 is larger and slower
+/// to produce, but some older tooling only accepts it.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TxEncoding {
+    #[default]
+    Base64,
+    Base58,
+}
+
+impl TxEncoding {
+    fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            TxEncoding::Base64 => base64::prelude::BASE64_STANDARD.encode(bytes),
+            TxEncoding::Base58 => bs58::encode(bytes).into_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TxEncodingParams {
+    #[serde(default)]
+    tx_encoding: TxEncoding,
+}
+
+/// Resolves an optional token program selector ("spl-token", "token-2022" or a
+/// program id) to a program id, falling back to `default` when absent.
+fn parse_token_program(token_program: Option<&str>, default: Pubkey) -> Result<Pubkey, String> {
+    match token_program.map(str::trim) {
+        None | Some("") => Ok(default),
+        Some("spl-token") => Ok(spl_token::id()),
+        Some("token-2022") => Ok(spl_token_2022::id()),
+        Some(other) => Pubkey::from_str(other).map_err(|_| "Invalid token_program".to_string()),
+    }
+}
+
+/// Resolves an optional `associated_token_program_id`, defaulting to the
+/// canonical associated-token program.
+fn parse_ata_program(ata_program: Option<&str>) -> Result<Pubkey, String> {
+    match ata_program.map(str::trim) {
+        None | Some("") => Ok(spl_associated_token_account::id()),
+        Some(other) => Pubkey::from_str(other).map_err(|_| "Invalid associated_token_program_id".to_string()),
+    }
+}
+
+/// Derives an ATA with the canonical seeds (wallet, token program, mint)
+/// under `ata_program`, which forked deployments may have redeployed.
+fn associated_token_address(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey, ata_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[wallet.as_ref(), token_program.as_ref(), mint.as_ref()], ata_program).0
+}
+
+// ===== 1. GENERATE KEYPAIR ENDPOINT =====
+
+#[derive(Serialize)]
+struct KeypairResponse {
+    pubkey: String,
+    secret: String,
+}
+
+async fn generate_keypair() -> impl IntoResponse {
+    let keypair = Keypair::new();
+    let pubkey = keypair.pubkey().to_string();
+    let secret_bytes = keypair.to_bytes();
+    let secret = bs58::encode(secret_bytes).into_string();
+
+    let response = KeypairResponse { pubkey, secret };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 2. CREATE TOKEN ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct TokenCreateRequest {
+    #[serde(rename = "mintAuthority")]
+    mint_authority: String,
+    mint: String,
+    decimals: u8,
+    // Checks on-chain that the mint account is still free before building
+    #[serde(default)]
+    preflight: bool,
+    rpc_url: Option<String>,
+    cluster: Option<String>,
+}
+
+/// Fails if `mint` can no longer be initialized: it is already a mint, or the
+/// address is taken by some other account. A freshly allocated, still
+/// uninitialized mint account passes.
+#[cfg(feature = "rpc")]
+async fn preflight_mint(rpc_url: Option<&str>, cluster: Option<&str>, mint: &Pubkey) -> Result<(), String> {
+    let client = rpc_client(rpc_url, cluster)?;
+
+    let account = client
+        .get_account_with_commitment(mint, CommitmentConfig::confirmed())
+        .await
+        .map_err(|e| format!("Failed to fetch mint account: {e}"))?
+        .value;
+
+    let Some(account) = account else {
+        return Ok(());
+    };
+
+    if account.owner != spl_token::id() || account.data.len() != spl_token::state::Mint::LEN {
+        return Err(format!("Mint account {mint} is already in use, owned by {}", account.owner));
+    }
+    if spl_token::state::Mint::unpack_unchecked(&account.data).is_ok_and(|state| state.is_initialized) {
+        return Err(format!("Mint {mint} is already initialized"));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "rpc"))]
+async fn preflight_mint(_rpc_url: Option<&str>, _cluster: Option<&str>, _mint: &Pubkey) -> Result<(), String> {
+    Err(RPC_DISABLED.to_string())
+}
+
+fn validate_create_token(req: &TokenCreateRequest) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    if Pubkey::from_str(&req.mint).is_err() {
+        errors.push(FieldError::new("mint", "Invalid mint pubkey"));
+    }
+    if Pubkey::from_str(&req.mint_authority).is_err() {
+        errors.push(FieldError::new("mintAuthority", "Invalid mint_authority pubkey"));
+    }
+
+    errors
+}
+
+async fn create_token(
+    Query(params): Query<ValidationParams>,
+    Query(checksum): Query<ChecksumParams>,
+    JsonBody(req): JsonBody<TokenCreateRequest>,
+) -> impl IntoResponse {
+    if params.validate_all {
+        let errors = validate_create_token(&req);
+        if !errors.is_empty() {
+            return Json(ApiResponse::invalid(errors));
+        }
+    }
+
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let mint_authority = match Pubkey::from_str(&req.mint_authority) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint_authority pubkey".into()))),
+    };
+
+    if req.preflight
+        && let Err(e) = preflight_mint(req.rpc_url.as_deref(), req.cluster.as_deref(), &mint).await
+    {
+        return Json(ApiResponse::from(Err(e)));
+    }
+
+    let freeze_authority = None;
+
+    let instruction = match initialize_mint(
+        &spl_token::id(),
+        &mint,
+        &mint_authority,
+        freeze_authority.as_ref(),
+        req.decimals,
+    ) {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build instruction: {e}")))),
+    };
+
+    let accounts: Vec<AccountMetaJson> = instruction
+        .accounts
+        .iter()
+        .map(AccountMetaJson::from)
+        .collect();
+
+    let instruction_data = base64::prelude::BASE64_STANDARD.encode(instruction.data.clone());
+
+    let response = InstructionJson {
+        program_id: instruction.program_id.to_string(),
+        accounts,
+        instruction_data,
+        instruction_data_hex: hex::encode(&instruction.data),
+        related_programs: related_programs(&instruction, &[]),
+    };
+
+    let response = with_checksum(response, &instruction.data, checksum.checksum);
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 3. MINT TOKEN ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct MintTokenRequest {
+    mint: String,
+    destination: String,
+    authority: String,
+    amount: u64,
+}
+
+async fn mint_token(
+    Query(checksum): Query<ChecksumParams>,
+    Query(guard): Query<AmountGuardParams>,
+    JsonBody(req): JsonBody<MintTokenRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = guard.check(req.amount) {
+        return Json(ApiResponse::from(Err(e)));
+    }
+
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let destination = match Pubkey::from_str(&req.destination) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid destination pubkey".into()))),
+    };
+
+    let authority = match Pubkey::from_str(&req.authority) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid authority pubkey".into()))),
+    };
+
+    let instruction = match mint_to(
+        &spl_token::id(),
+        &mint,
+        &destination,
+        &authority,
+        &[], // no multisig signers
+        req.amount,
+    ) {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build mint instruction: {}", e)))),
+    };
+
+    let accounts: Vec<AccountMetaJson> = instruction
+        .accounts
+        .iter()
+        .map(AccountMetaJson::from)
+        .collect();
+
+    let response = InstructionJson {
+        program_id: instruction.program_id.to_string(),
+        accounts,
+        instruction_data: base64::prelude::BASE64_STANDARD.encode(instruction.data.clone()),
+        instruction_data_hex: hex::encode(&instruction.data),
+        related_programs: related_programs(&instruction, &[]),
+    };
+
+    let response = with_checksum(response, &instruction.data, checksum.checksum);
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 4. SIGN MESSAGE ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct SignMessageRequest {
+    message: String,
+    secret: String,
+}
 
 #[derive(Serialize)]
 struct SignMessageResponse {
     signature: String,
     public_key: String,
-    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
 }
 
-async fn sign_message(Json(req): Json<SignMessageRequest>) -> impl IntoResponse {
+async fn sign_message(
+    Query(echo): Query<EchoParams>,
+    JsonBody(req): JsonBody<SignMessageRequest>,
+) -> impl IntoResponse {
     if req.message.trim().is_empty() || req.secret.trim().is_empty() {
         return Json(ApiResponse::<SignMessageResponse>::Error {
             success: false,
@@ -245,24 +1168,13 @@ async fn sign_message(Json(req): Json<SignMessageRequest>) -> impl IntoResponse
         });
     }
 
-    // Decode base58-encoded secret key
-    let secret_bytes = match bs58::decode(&req.secret).into_vec() {
-        Ok(bytes) if bytes.len() == 64 => bytes,
-        _ => {
-            return Json(ApiResponse::<SignMessageResponse>::Error {
-                success: false,
-                error: "Invalid secret key".to_string(),
-            });
-        }
-    };
-
-    // Create Keypair from bytes
-    let keypair = match Keypair::from_bytes(&secret_bytes) {
+    // Decode the secret key (base58 or Solana CLI keyfile byte array)
+    let keypair = match parse_keypair(&req.secret) {
         Ok(kp) => kp,
-        Err(_) => {
+        Err(error) => {
             return Json(ApiResponse::<SignMessageResponse>::Error {
                 success: false,
-                error: "Failed to construct keypair".to_string(),
+                error,
             });
         }
     };
@@ -273,7 +1185,7 @@ async fn sign_message(Json(req): Json<SignMessageRequest>) -> impl IntoResponse
     let response = SignMessageResponse {
         signature: base64::prelude::BASE64_STANDARD.encode(signature.as_ref()),
         public_key: keypair.pubkey().to_string(),
-        message: req.message.clone(),
+        message: echo.echo_message.then(|| req.message.clone()),
     };
 
     Json(ApiResponse::from(Ok(response)))
@@ -286,16 +1198,38 @@ struct VerifyMessageRequest {
     message: String,
     signature: String, // base64-encoded signature
     pubkey: String,    // base58-encoded public key
+    // The signer signed the SHA-256 of the message rather than the message itself
+    #[serde(default)]
+    pre_hash: bool,
+    // Also reject non-canonical encodings and report why
+    #[serde(default)]
+    strict: bool,
 }
 
 #[derive(Serialize)]
 struct VerifyMessageResponse {
     valid: bool,
-    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
     pubkey: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
 }
 
-async fn verify_message(Json(req): Json<VerifyMessageRequest>) -> impl IntoResponse {
+fn invalid_pubkey_error(value: &str) -> &'static str {
+    // A 64-byte value is almost always a secret key pasted by mistake
+    let is_secret_key = matches!(bs58::decode(value).into_vec(), Ok(bytes) if bytes.len() == 64);
+    if is_secret_key {
+        "Invalid public key: this looks like a secret key, expected a 32-byte public key"
+    } else {
+        "Invalid public key"
+    }
+}
+
+async fn verify_message(
+    Query(echo): Query<EchoParams>,
+    JsonBody(req): JsonBody<VerifyMessageRequest>,
+) -> impl IntoResponse {
     // Validate input fields
     if req.message.trim().is_empty() || req.signature.trim().is_empty() || req.pubkey.trim().is_empty() {
         return Json(ApiResponse::<VerifyMessageResponse>::Error {
@@ -310,7 +1244,7 @@ async fn verify_message(Json(req): Json<VerifyMessageRequest>) -> impl IntoRespo
         Err(_) => {
             return Json(ApiResponse::<VerifyMessageResponse>::Error {
                 success: false,
-                error: "Invalid public key".to_string(),
+                error: invalid_pubkey_error(&req.pubkey).to_string(),
             });
         }
     };
@@ -338,12 +1272,26 @@ async fn verify_message(Json(req): Json<VerifyMessageRequest>) -> impl IntoRespo
     };
 
     // Verify the signature using Ed25519
-    let is_valid = signature.verify(&pubkey.to_bytes(), req.message.as_bytes());
+    let signed_bytes = if req.pre_hash {
+        Sha256::digest(req.message.as_bytes()).to_vec()
+    } else {
+        req.message.as_bytes().to_vec()
+    };
+    let mut is_valid = signature.verify(&pubkey.to_bytes(), &signed_bytes);
+
+    let reason = if req.strict {
+        let check = check_canonical_signature(&signature, &pubkey, &signed_bytes);
+        is_valid &= check.reason.is_none();
+        check.reason
+    } else {
+        None
+    };
 
     let response = VerifyMessageResponse {
         valid: is_valid,
-        message: req.message.clone(),
+        message: echo.echo_message.then(|| req.message.clone()),
         pubkey: req.pubkey.clone(),
+        reason,
     };
 
     Json(ApiResponse::from(Ok(response)))
@@ -356,6 +1304,9 @@ struct SendSolRequest {
     from: String,
     to: String,
     lamports: u64,
+    // When set, checks that `from` can cover lamports plus the fee
+    rpc_url: Option<String>,
+    cluster: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -363,14 +1314,60 @@ struct SendSolResponse {
     program_id: String,
     accounts: Vec<String>,
     instruction_data: String,
+    instruction_data_hex: String,
+    related_programs: Vec<RelatedProgram>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
+}
+
+/// Warns when `from` holds less than `lamports` plus the current transfer fee.
+/// Only a hint: the balance can change before the transaction lands.
+#[cfg(feature = "rpc")]
+async fn check_sol_funding(
+    rpc_url: Option<&str>,
+    cluster: Option<&str>,
+    from: &Pubkey,
+    lamports: u64,
+) -> Result<Option<String>, String> {
+    let client = rpc_client(rpc_url, cluster)?;
+
+    let fee = estimate_transfer_fee(&client).await?;
+    let balance = client
+        .get_balance_with_commitment(from, CommitmentConfig::confirmed())
+        .await
+        .map_err(|e| format!("Failed to fetch sender balance: {e}"))?
+        .value;
+
+    let required = lamports.saturating_add(fee);
+    Ok((balance < required).then(|| {
+        format!("Sender balance of {balance} lamports is below the {required} needed ({lamports} plus a {fee} fee)")
+    }))
 }
 
-async fn send_sol(Json(req): Json<SendSolRequest>) -> impl IntoResponse {
+#[cfg(not(feature = "rpc"))]
+async fn check_sol_funding(
+    _rpc_url: Option<&str>,
+    _cluster: Option<&str>,
+    _from: &Pubkey,
+    _lamports: u64,
+) -> Result<Option<String>, String> {
+    Err(RPC_DISABLED.to_string())
+}
+
+async fn send_sol(
+    Query(checksum): Query<ChecksumParams>,
+    Query(guard): Query<AmountGuardParams>,
+    JsonBody(req): JsonBody<SendSolRequest>,
+) -> impl IntoResponse {
     // Validate that lamports is greater than 0
     if req.lamports == 0 {
         return Json(ApiResponse::from(Err("Amount must be greater than 0".into())));
     }
 
+    if let Err(e) = guard.check(req.lamports) {
+        return Json(ApiResponse::from(Err(e)));
+    }
+
     // Validate addresses
     let from = match Pubkey::from_str(&req.from) {
         Ok(p) => p,
@@ -387,6 +1384,15 @@ async fn send_sol(Json(req): Json<SendSolRequest>) -> impl IntoResponse {
         return Json(ApiResponse::from(Err("Sender and recipient cannot be the same".into())));
     }
 
+    let warning = if req.rpc_url.is_some() || req.cluster.is_some() {
+        match check_sol_funding(req.rpc_url.as_deref(), req.cluster.as_deref(), &from, req.lamports).await {
+            Ok(warning) => warning,
+            Err(e) => return Json(ApiResponse::from(Err(e))),
+        }
+    } else {
+        None
+    };
+
     // Create instruction
     let instruction = transfer(&from, &to, req.lamports);
 
@@ -403,8 +1409,13 @@ async fn send_sol(Json(req): Json<SendSolRequest>) -> impl IntoResponse {
         program_id: instruction.program_id.to_string(),
         accounts,
         instruction_data,
+        instruction_data_hex: hex::encode(&instruction.data),
+        related_programs: related_programs(&instruction, &[]),
+        warning,
     };
 
+    let response = with_checksum(response, &instruction.data, checksum.checksum);
+
     Json(ApiResponse::from(Ok(response)))
 }
 
@@ -415,7 +1426,57 @@ struct SendTokenRequest {
     destination: String, // destination user address (wallet pubkey)
     mint: String,        // mint address
     owner: String,       // owner address (source wallet pubkey)  
-    amount: u64,         // amount to transfer
+    amount: TokenAmountInput, // amount to transfer, or "max"
+    source_token_account: Option<String>, // overrides the owner's ATA as the source
+    rpc_url: Option<String>, // used to look up the balance for "max"
+    cluster: Option<String>,
+}
+
+/// A raw token amount, or the keyword "max" for the source account's whole balance.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TokenAmountInput {
+    Exact(u64),
+    Keyword(String),
+}
+
+/// Raw balance of an spl-token account holding `mint`, read from its on-chain state.
+#[cfg(feature = "rpc")]
+async fn fetch_token_balance(
+    rpc_url: Option<&str>,
+    cluster: Option<&str>,
+    token_account: &Pubkey,
+    mint: &Pubkey,
+) -> Result<u64, String> {
+    let client = rpc_client(rpc_url, cluster)?;
+
+    let account = client
+        .get_account_with_commitment(token_account, CommitmentConfig::confirmed())
+        .await
+        .map_err(|e| format!("Failed to fetch source token account: {e}"))?
+        .value
+        .ok_or_else(|| format!("Source token account {token_account} does not exist"))?;
+
+    if account.owner != spl_token::id() {
+        return Err(format!("{token_account} is not an spl-token account"));
+    }
+    let state = spl_token::state::Account::unpack(&account.data)
+        .map_err(|_| format!("{token_account} is not an initialized token account"))?;
+    if state.mint != *mint {
+        return Err(format!("Source token account {token_account} holds a different mint"));
+    }
+
+    Ok(state.amount)
+}
+
+#[cfg(not(feature = "rpc"))]
+async fn fetch_token_balance(
+    _rpc_url: Option<&str>,
+    _cluster: Option<&str>,
+    _token_account: &Pubkey,
+    _mint: &Pubkey,
+) -> Result<u64, String> {
+    Err(RPC_DISABLED.to_string())
 }
 
 #[derive(Serialize)]
@@ -423,6 +1484,11 @@ struct SendTokenResponse {
     program_id: String,
     accounts: Vec<SendTokenAccount>,
     instruction_data: String,
+    instruction_data_hex: String,
+    related_programs: Vec<RelatedProgram>,
+    // The balance that was looked up when amount was "max"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolved_amount: Option<BigUint>,
 }
 
 #[derive(Serialize)]
@@ -433,10 +1499,68 @@ struct SendTokenAccount {
     // The spec shows inconsistent naming - keeping both for compatibility
 }
 
-async fn send_token(Json(req): Json<SendTokenRequest>) -> impl IntoResponse {
+fn validate_send_token(req: &SendTokenRequest) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    match &req.amount {
+        TokenAmountInput::Exact(0) => errors.push(FieldError::new("amount", "Amount must be greater than 0")),
+        TokenAmountInput::Keyword(keyword) if keyword != "max" => {
+            errors.push(FieldError::new("amount", "Amount must be a number or \"max\""));
+        }
+        _ => {}
+    }
+    if Pubkey::from_str(&req.mint).is_err() {
+        errors.push(FieldError::new("mint", "Invalid mint pubkey"));
+    }
+
+    let owner = Pubkey::from_str(&req.owner);
+    if owner.is_err() {
+        errors.push(FieldError::new("owner", "Invalid owner pubkey"));
+    }
+
+    let destination = Pubkey::from_str(&req.destination);
+    if destination.is_err() {
+        errors.push(FieldError::new("destination", "Invalid destination pubkey"));
+    }
+
+    if let Some(source) = &req.source_token_account
+        && Pubkey::from_str(source).is_err()
+    {
+        errors.push(FieldError::new("source_token_account", "Invalid source_token_account pubkey"));
+    }
+
+    if let (Ok(owner), Ok(destination)) = (owner, destination)
+        && owner == destination
+    {
+        errors.push(FieldError::new("destination", "Owner and destination cannot be the same"));
+    }
+
+    errors
+}
+
+async fn send_token(
+    Query(params): Query<ValidationParams>,
+    Query(checksum): Query<ChecksumParams>,
+    Query(guard): Query<AmountGuardParams>,
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<SendTokenRequest>,
+) -> impl IntoResponse {
+    if params.validate_all {
+        let errors = validate_send_token(&req);
+        if !errors.is_empty() {
+            return Json(ApiResponse::invalid(errors));
+        }
+    }
+
     // Validate that amount is greater than 0
-    if req.amount == 0 {
-        return Json(ApiResponse::from(Err("Amount must be greater than 0".into())));
+    match &req.amount {
+        TokenAmountInput::Exact(0) => {
+            return Json(ApiResponse::from(Err("Amount must be greater than 0".into())));
+        }
+        TokenAmountInput::Keyword(keyword) if keyword != "max" => {
+            return Json(ApiResponse::from(Err("Amount must be a number or \"max\"".into())));
+        }
+        _ => {}
     }
 
     // Parse public keys
@@ -460,27 +1584,122 @@ async fn send_token(Json(req): Json<SendTokenRequest>) -> impl IntoResponse {
         return Json(ApiResponse::from(Err("Owner and destination cannot be the same".into())));
     }
 
-    // Derive associated token accounts
-    let source_ata = get_associated_token_address(&owner, &mint);
+    // Derive associated token accounts, unless the tokens sit in a non-associated account
+    let source_ata = match req.source_token_account.as_deref().map(Pubkey::from_str) {
+        Some(Ok(p)) => p,
+        Some(Err(_)) => return Json(ApiResponse::from(Err("Invalid source_token_account pubkey".into()))),
+        None => get_associated_token_address(&owner, &mint),
+    };
     let destination_ata = get_associated_token_address(&destination_wallet, &mint);
 
+    let (amount, resolved_amount) = match req.amount {
+        TokenAmountInput::Exact(amount) => (amount, None),
+        TokenAmountInput::Keyword(_) => {
+            match fetch_token_balance(req.rpc_url.as_deref(), req.cluster.as_deref(), &source_ata, &mint).await {
+                Ok(0) => return Json(ApiResponse::from(Err("Source token account has a zero balance".into()))),
+                Ok(balance) => (balance, Some(bigint.wrap(balance))),
+                Err(e) => return Json(ApiResponse::from(Err(e))),
+            }
+        }
+    };
+
+    if let Err(e) = guard.check(amount) {
+        return Json(ApiResponse::from(Err(e)));
+    }
+
     // Create transfer instruction
-    let instruction_result = spl_transfer(
+    let instruction = match spl_transfer(
         &spl_token::id(),
         &source_ata,      // source token account
-        &destination_ata, // destination token account  
+        &destination_ata, // destination token account
         &owner,           // owner of source account
         &[],              // no multisig signers
-        req.amount,       // amount to transfer
-    );
+        amount,           // amount to transfer
+    ) {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build transfer instruction: {}", e)))),
+    };
 
-    if let Err(e) = instruction_result {
-        return Json(ApiResponse::from(Err(format!("Failed to build transfer instruction: {}", e))));
+    // Convert accounts to the required format (only pubkey and isSigner per spec)
+    let accounts: Vec<SendTokenAccount> = instruction
+        .accounts
+        .iter()
+        .map(|meta| SendTokenAccount {
+            pubkey: meta.pubkey.to_string(),
+            is_signer: meta.is_signer,
+        })
+        .collect();
+
+    // The associated token program only matters when both accounts were derived
+    // through it; an explicit source_token_account may be any token account
+    let ata_program: &[Pubkey] = if req.source_token_account.is_none() {
+        &[spl_associated_token_account::id()]
+    } else {
+        &[]
+    };
+
+    let response = SendTokenResponse {
+        program_id: instruction.program_id.to_string(),
+        accounts,
+        instruction_data: base64::prelude::BASE64_STANDARD.encode(instruction.data.clone()),
+        instruction_data_hex: hex::encode(&instruction.data),
+        related_programs: related_programs(&instruction, ata_program),
+        resolved_amount,
+    };
+
+    let response = with_checksum(response, &instruction.data, checksum.checksum);
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+#[derive(Debug, Deserialize)]
+struct SendTokenAsDelegateRequest {
+    source_token_account: String,
+    destination_token_account: String,
+    delegate: String,
+    amount: u64,
+}
+
+/// Same transfer as `/send/token`, but between raw token accounts and signed by an
+/// approved delegate instead of the source account's owner.
+async fn send_token_as_delegate(
+    Query(guard): Query<AmountGuardParams>,
+    JsonBody(req): JsonBody<SendTokenAsDelegateRequest>,
+) -> impl IntoResponse {
+    if req.amount == 0 {
+        return Json(ApiResponse::from(Err("Amount must be greater than 0".into())));
     }
 
-    let instruction = instruction_result.unwrap();
+    if let Err(e) = guard.check(req.amount) {
+        return Json(ApiResponse::from(Err(e)));
+    }
+
+    let source = match Pubkey::from_str(&req.source_token_account) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid source_token_account pubkey".into()))),
+    };
+
+    let destination = match Pubkey::from_str(&req.destination_token_account) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid destination_token_account pubkey".into()))),
+    };
+
+    let delegate = match Pubkey::from_str(&req.delegate) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid delegate pubkey".into()))),
+    };
+
+    if source == destination {
+        return Json(ApiResponse::from(Err("Source and destination cannot be the same".into())));
+    }
+
+    // The token program accepts the delegate in the authority slot as long as
+    // the approved allowance covers the amount
+    let instruction = match spl_transfer(&spl_token::id(), &source, &destination, &delegate, &[], req.amount) {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build transfer instruction: {}", e)))),
+    };
 
-    // Convert accounts to the required format (only pubkey and isSigner per spec)
     let accounts: Vec<SendTokenAccount> = instruction
         .accounts
         .iter()
@@ -493,8 +1712,5355 @@ async fn send_token(Json(req): Json<SendTokenRequest>) -> impl IntoResponse {
     let response = SendTokenResponse {
         program_id: instruction.program_id.to_string(),
         accounts,
-        instruction_data: base64::prelude::BASE64_STANDARD.encode(instruction.data.clone()),
+        instruction_data: base64::prelude::BASE64_STANDARD.encode(&instruction.data),
+        instruction_data_hex: hex::encode(&instruction.data),
+        related_programs: related_programs(&instruction, &[]),
+        resolved_amount: None,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 8. QR CODE ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct QrRequest {
+    data: String,   // typically a pubkey or Solana Pay URL
+    format: String, // "svg" or "png-base64"
+}
+
+#[derive(Serialize)]
+struct QrResponse {
+    format: String,
+    content: String,
+}
+
+async fn generate_qr(JsonBody(req): JsonBody<QrRequest>) -> impl IntoResponse {
+    if req.data.trim().is_empty() {
+        return Json(ApiResponse::from(Err("Missing required fields".into())));
+    }
+
+    let code = match qrcode::QrCode::new(req.data.as_bytes()) {
+        Ok(c) => c,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to encode QR code: {e}")))),
+    };
+
+    let content = match req.format.as_str() {
+        "svg" => code
+            .render::<qrcode::render::svg::Color>()
+            .min_dimensions(200, 200)
+            .build(),
+        "png-base64" => {
+            let image = code.render::<image::Luma<u8>>().min_dimensions(200, 200).build();
+
+            let mut png = std::io::Cursor::new(Vec::new());
+            if let Err(e) = image.write_to(&mut png, image::ImageFormat::Png) {
+                return Json(ApiResponse::from(Err(format!("Failed to render PNG: {e}"))));
+            }
+
+            base64::prelude::BASE64_STANDARD.encode(png.into_inner())
+        }
+        _ => return Json(ApiResponse::from(Err("Invalid format, expected svg or png-base64".into()))),
+    };
+
+    let response = QrResponse {
+        format: req.format,
+        content,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 9. PARTIAL TRANSACTION SIGNING ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct SignPartialRequest {
+    transaction: String, // base64-encoded serialized transaction
+    secret: String,      // base58-encoded secret key of one required signer
+}
+
+#[derive(Serialize)]
+struct SignPartialResponse {
+    transaction: String,
+    signer: String,
+}
+
+async fn sign_partial_transaction(
+    Query(encoding): Query<TxEncodingParams>,
+    JsonBody(req): JsonBody<SignPartialRequest>,
+) -> impl IntoResponse {
+    if req.transaction.trim().is_empty() || req.secret.trim().is_empty() {
+        return Json(ApiResponse::from(Err("Missing required fields".into())));
+    }
+
+    let keypair = match parse_keypair(&req.secret) {
+        Ok(kp) => kp,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let mut tx = match decode_transaction(&req.transaction) {
+        Ok(tx) => tx,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    // Only the first `num_required_signatures` account keys are signer slots
+    let signer = keypair.pubkey();
+    let required = tx.message.header.num_required_signatures as usize;
+    if !tx.message.account_keys[..required].contains(&signer) {
+        return Json(ApiResponse::from(Err(format!(
+            "{signer} is not a required signer of this transaction"
+        ))));
+    }
+
+    // Fills only this signer's slot, leaving the other signatures untouched
+    let recent_blockhash = tx.message.recent_blockhash;
+    if let Err(e) = tx.try_partial_sign(&[&keypair], recent_blockhash) {
+        return Json(ApiResponse::from(Err(format!("Failed to sign transaction: {e}"))));
+    }
+
+    let transaction = match encode_transaction(&tx, encoding.tx_encoding) {
+        Ok(encoded) => encoded,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let response = SignPartialResponse {
+        transaction,
+        signer: signer.to_string(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 10. TRANSACTION SIGNING STATUS ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct SigningStatusRequest {
+    transaction: String, // base64-encoded serialized transaction
+}
+
+#[derive(Serialize)]
+struct SigningStatusResponse {
+    required: usize,
+    present: usize,
+    missing_signers: Vec<String>,
+}
+
+async fn signing_status(JsonBody(req): JsonBody<SigningStatusRequest>) -> impl IntoResponse {
+    if req.transaction.trim().is_empty() {
+        return Json(ApiResponse::from(Err("Missing required fields".into())));
+    }
+
+    let tx = match decode_transaction(&req.transaction) {
+        Ok(tx) => tx,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    // An all-zero signature marks a slot that has not been signed yet
+    let missing_signers: Vec<String> = tx
+        .signatures
+        .iter()
+        .zip(tx.message.account_keys.iter())
+        .filter(|(signature, _)| **signature == Signature::default())
+        .map(|(_, pubkey)| pubkey.to_string())
+        .collect();
+
+    let required = tx.signatures.len();
+
+    let response = SigningStatusResponse {
+        required,
+        present: required - missing_signers.len(),
+        missing_signers,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 11. LEGACY TO V0 TRANSACTION UPGRADE ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct UpgradeV0Request {
+    transaction: String, // base64-encoded serialized legacy transaction
+}
+
+#[derive(Serialize)]
+struct UpgradeV0Response {
+    transaction: String,
+    version: String,
+}
+
+async fn upgrade_to_v0(
+    Query(encoding): Query<TxEncodingParams>,
+    JsonBody(req): JsonBody<UpgradeV0Request>,
+) -> impl IntoResponse {
+    if req.transaction.trim().is_empty() {
+        return Json(ApiResponse::from(Err("Missing required fields".into())));
+    }
+
+    let tx = match decode_transaction(&req.transaction) {
+        Ok(tx) => tx,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    // Signatures cover the serialized message, and a v0 message serializes with a
+    // version prefix, so even with identical account order they cannot carry over
+    if tx.signatures.iter().any(|signature| *signature != Signature::default()) {
+        return Json(ApiResponse::from(Err(
+            "Transaction is already signed; signatures do not survive the upgrade and must be redone on the v0 transaction".into(),
+        )));
+    }
+
+    // Reuse the legacy layout as-is (no lookup tables) so account order is unchanged
+    let message = v0::Message {
+        header: tx.message.header,
+        account_keys: tx.message.account_keys,
+        recent_blockhash: tx.message.recent_blockhash,
+        instructions: tx.message.instructions,
+        address_table_lookups: vec![],
+    };
+
+    let upgraded = VersionedTransaction {
+        signatures: tx.signatures,
+        message: VersionedMessage::V0(message),
+    };
+
+    let transaction = match bincode::serialize(&upgraded) {
+        Ok(bytes) => encoding.tx_encoding.encode(&bytes),
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to serialize transaction: {e}")))),
+    };
+
+    let response = UpgradeV0Response {
+        transaction,
+        version: "v0".to_string(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 12. MINT RENT WITH EXTENSIONS ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct MintRentRequest {
+    extensions: Vec<String>, // snake_case Token-2022 mint extension names
+}
+
+#[derive(Serialize)]
+struct MintRentResponse {
+    account_len: usize,
+    lamports: BigUint,
+}
+
+/// Maps a snake_case extension name to a fixed-size Token-2022 mint extension.
+fn parse_mint_extension(name: &str) -> Result<ExtensionType, String> {
+    let extension = match name {
+        "transfer_fee_config" => ExtensionType::TransferFeeConfig,
+        "mint_close_authority" => ExtensionType::MintCloseAuthority,
+        "confidential_transfer_mint" => ExtensionType::ConfidentialTransferMint,
+        "default_account_state" => ExtensionType::DefaultAccountState,
+        "non_transferable" => ExtensionType::NonTransferable,
+        "interest_bearing_config" => ExtensionType::InterestBearingConfig,
+        "permanent_delegate" => ExtensionType::PermanentDelegate,
+        "transfer_hook" => ExtensionType::TransferHook,
+        "confidential_transfer_fee_config" => ExtensionType::ConfidentialTransferFeeConfig,
+        "metadata_pointer" => ExtensionType::MetadataPointer,
+        "group_pointer" => ExtensionType::GroupPointer,
+        "token_group" => ExtensionType::TokenGroup,
+        "group_member_pointer" => ExtensionType::GroupMemberPointer,
+        "token_group_member" => ExtensionType::TokenGroupMember,
+        "token_metadata" => {
+            return Err("token_metadata is variable-length and cannot be sized up front".to_string());
+        }
+        _ => return Err(format!("Unknown mint extension: {name}")),
+    };
+
+    Ok(extension)
+}
+
+async fn mint_rent(
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<MintRentRequest>,
+) -> impl IntoResponse {
+    let mut extensions = Vec::with_capacity(req.extensions.len());
+    for name in &req.extensions {
+        match parse_mint_extension(name.trim()) {
+            Ok(extension) if extensions.contains(&extension) => {
+                return Json(ApiResponse::from(Err(format!("Duplicate mint extension: {name}"))));
+            }
+            Ok(extension) => extensions.push(extension),
+            Err(e) => return Json(ApiResponse::from(Err(e))),
+        }
+    }
+
+    let account_len =
+        match ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extensions) {
+            Ok(len) => len,
+            Err(e) => return Json(ApiResponse::from(Err(format!("Failed to compute mint size: {e}")))),
+        };
+
+    let response = MintRentResponse {
+        account_len,
+        lamports: bigint.wrap(Rent::default().minimum_balance(account_len)),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 13. KEYPAIR FORMAT CONVERSION ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct ConvertKeypairRequest {
+    secret: String,
+    from: String, // "base58", "hex" or "array"
+    to: String,   // "base58", "hex" or "array"
+}
+
+fn decode_secret(secret: &str, format: &str) -> Result<Vec<u8>, String> {
+    let bytes = match format {
+        "base58" => bs58::decode(secret).into_vec().map_err(|_| "Invalid base58 secret".to_string())?,
+        "hex" => hex::decode(secret).map_err(|_| "Invalid hex secret".to_string())?,
+        "array" => serde_json::from_str::<Vec<u8>>(secret)
+            .map_err(|_| "Invalid byte array secret".to_string())?,
+        _ => return Err(format!("Unsupported format: {format}")),
+    };
+
+    if bytes.len() != 64 {
+        return Err(format!("Secret key must be 64 bytes, got {}", bytes.len()));
+    }
+
+    Ok(bytes)
+}
+
+fn encode_secret(bytes: &[u8], format: &str) -> Result<String, String> {
+    match format {
+        "base58" => Ok(bs58::encode(bytes).into_string()),
+        "hex" => Ok(hex::encode(bytes)),
+        "array" => serde_json::to_string(bytes).map_err(|e| format!("Failed to encode secret: {e}")),
+        _ => Err(format!("Unsupported format: {format}")),
+    }
+}
+
+async fn convert_keypair(JsonBody(req): JsonBody<ConvertKeypairRequest>) -> impl IntoResponse {
+    if req.secret.trim().is_empty() {
+        return Json(ApiResponse::from(Err("Missing required fields".into())));
+    }
+
+    let secret_bytes = match decode_secret(req.secret.trim(), &req.from) {
+        Ok(bytes) => bytes,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    // Round-trip through Keypair so a mismatched secret/public half is rejected
+    let keypair = match Keypair::from_bytes(&secret_bytes) {
+        Ok(kp) => kp,
+        Err(_) => return Json(ApiResponse::from(Err("Failed to construct keypair".into()))),
+    };
+
+    let secret = match encode_secret(&keypair.to_bytes(), &req.to) {
+        Ok(encoded) => encoded,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let response = KeypairResponse {
+        pubkey: keypair.pubkey().to_string(),
+        secret,
     };
 
     Json(ApiResponse::from(Ok(response)))
-}
\ No newline at end of file
+}
+
+// ===== 14. BURN CHECKED ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct BurnCheckedRequest {
+    account: String,   // token account to burn from
+    mint: String,
+    authority: String, // owner or delegate of the token account
+    amount: u64,
+    decimals: u8,      // must match the mint's decimals or the program rejects the burn
+}
+
+async fn burn_token_checked(
+    Query(guard): Query<AmountGuardParams>,
+    JsonBody(req): JsonBody<BurnCheckedRequest>,
+) -> impl IntoResponse {
+    if req.amount == 0 {
+        return Json(ApiResponse::from(Err("Amount must be greater than 0".into())));
+    }
+
+    if let Err(e) = guard.check(req.amount) {
+        return Json(ApiResponse::from(Err(e)));
+    }
+
+    let account = match Pubkey::from_str(&req.account) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid account pubkey".into()))),
+    };
+
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let authority = match Pubkey::from_str(&req.authority) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid authority pubkey".into()))),
+    };
+
+    let instruction = match burn_checked(
+        &spl_token::id(),
+        &account,
+        &mint,
+        &authority,
+        &[], // no multisig signers
+        req.amount,
+        req.decimals,
+    ) {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build burn instruction: {e}")))),
+    };
+
+    Json(ApiResponse::from(Ok(InstructionJson::from(instruction))))
+}
+
+// ===== 15. MINT CLOSE AUTHORITY INIT ENDPOINT (TOKEN-2022) =====
+
+#[derive(Debug, Deserialize)]
+struct MintCloseAuthorityRequest {
+    mint: String,
+    close_authority: String,
+    token_program: Option<String>, // defaults to token-2022; classic spl-token is rejected
+}
+
+async fn init_mint_close_authority(JsonBody(req): JsonBody<MintCloseAuthorityRequest>) -> impl IntoResponse {
+    let token_program = match parse_token_program(req.token_program.as_deref(), spl_token_2022::id()) {
+        Ok(p) => p,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    if token_program == spl_token::id() {
+        return Json(ApiResponse::from(Err(
+            "Mint close authority is a Token-2022 extension and is not supported by spl-token".into(),
+        )));
+    }
+
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let close_authority = match Pubkey::from_str(&req.close_authority) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid close_authority pubkey".into()))),
+    };
+
+    // Must run after the mint account is created but before initialize_mint
+    let instruction = match spl_token_2022::instruction::initialize_mint_close_authority(
+        &token_program,
+        &mint,
+        Some(&close_authority),
+    ) {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build instruction: {e}")))),
+    };
+
+    Json(ApiResponse::from(Ok(InstructionJson::from(instruction))))
+}
+
+// ===== 16. CLOSE MINT ENDPOINT (TOKEN-2022) =====
+
+#[derive(Debug, Deserialize)]
+struct CloseMintRequest {
+    mint: String,
+    destination: String,     // receives the reclaimed rent lamports
+    close_authority: String, // must match the mint's close authority extension
+}
+
+async fn close_mint(JsonBody(req): JsonBody<CloseMintRequest>) -> impl IntoResponse {
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let destination = match Pubkey::from_str(&req.destination) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid destination pubkey".into()))),
+    };
+
+    let close_authority = match Pubkey::from_str(&req.close_authority) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid close_authority pubkey".into()))),
+    };
+
+    if mint == destination {
+        return Json(ApiResponse::from(Err("Mint and destination cannot be the same".into())));
+    }
+
+    // Token-2022 only allows closing a mint with zero supply and a close authority set
+    let instruction = match spl_token_2022::instruction::close_account(
+        &spl_token_2022::id(),
+        &mint,
+        &destination,
+        &close_authority,
+        &[], // no multisig signers
+    ) {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build close instruction: {e}")))),
+    };
+
+    Json(ApiResponse::from(Ok(InstructionJson::from(instruction))))
+}
+
+// ===== 17. TRANSACTION SIZE ENDPOINT =====
+
+#[derive(Deserialize)]
+struct TxSizeRequest {
+    payer: String,
+    instructions: Vec<InstructionJson>,
+    signer_count: usize,
+}
+
+#[derive(Serialize)]
+struct TxSizeResponse {
+    size: usize,
+    limit: usize,
+    fits: bool,
+}
+
+/// Length of the compact-u16 prefix Solana uses for array lengths.
+fn short_vec_len(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+async fn transaction_size(JsonBody(req): JsonBody<TxSizeRequest>) -> impl IntoResponse {
+    if req.instructions.is_empty() {
+        return Json(ApiResponse::from(Err("At least one instruction is required".into())));
+    }
+
+    let payer = match Pubkey::from_str(&req.payer) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid payer pubkey".into()))),
+    };
+
+    let mut instructions = Vec::with_capacity(req.instructions.len());
+    for (i, ix) in req.instructions.iter().enumerate() {
+        match ix.to_instruction() {
+            Ok(instruction) => instructions.push(instruction),
+            Err(e) => return Json(ApiResponse::from(Err(format!("Instruction {i}: {e}")))),
+        }
+    }
+
+    let message = Message::new(&instructions, Some(&payer));
+
+    // A transaction carries exactly one signature per required signer, no more
+    let required = message.header.num_required_signatures as usize;
+    if req.signer_count != required {
+        return Json(ApiResponse::from(Err(format!(
+            "Message requires {required} signatures, signer_count is {}",
+            req.signer_count
+        ))));
+    }
+
+    // A serialized transaction is the signature array followed by the message
+    let size = short_vec_len(req.signer_count) + req.signer_count * 64 + message.serialize().len();
+
+    let response = TxSizeResponse {
+        size,
+        limit: PACKET_DATA_SIZE,
+        fits: size <= PACKET_DATA_SIZE,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 18. TRANSACTION DECODE ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct DecodeTxRequest {
+    transaction: String, // base64-encoded serialized transaction
+}
+
+#[derive(Serialize)]
+struct DecodeTxResponse {
+    signatures: Vec<String>,
+    message: DecodedMessage,
+}
+
+#[derive(Serialize)]
+struct DecodedMessage {
+    header: DecodedHeader,
+    account_keys: Vec<String>,
+    recent_blockhash: String,
+    instructions: Vec<DecodedInstruction>,
+}
+
+#[derive(Serialize)]
+struct DecodedHeader {
+    num_required_signatures: u8,
+    num_readonly_signed_accounts: u8,
+    num_readonly_unsigned_accounts: u8,
+}
+
+#[derive(Serialize)]
+struct DecodedInstruction {
+    program_id_index: u8,
+    accounts: Vec<u8>,
+    data_base64: String,
+    data_hex: String,
+}
+
+async fn decode_transaction_json(JsonBody(req): JsonBody<DecodeTxRequest>) -> impl IntoResponse {
+    if req.transaction.trim().is_empty() {
+        return Json(ApiResponse::from(Err("Missing required fields".into())));
+    }
+
+    let tx = match decode_transaction(&req.transaction) {
+        Ok(tx) => tx,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let header = DecodedHeader {
+        num_required_signatures: tx.message.header.num_required_signatures,
+        num_readonly_signed_accounts: tx.message.header.num_readonly_signed_accounts,
+        num_readonly_unsigned_accounts: tx.message.header.num_readonly_unsigned_accounts,
+    };
+
+    let instructions = tx
+        .message
+        .instructions
+        .iter()
+        .map(|ix| DecodedInstruction {
+            program_id_index: ix.program_id_index,
+            accounts: ix.accounts.clone(),
+            data_base64: base64::prelude::BASE64_STANDARD.encode(&ix.data),
+            data_hex: hex::encode(&ix.data),
+        })
+        .collect();
+
+    let response = DecodeTxResponse {
+        signatures: tx.signatures.iter().map(|sig| sig.to_string()).collect(),
+        message: DecodedMessage {
+            header,
+            account_keys: tx.message.account_keys.iter().map(|key| key.to_string()).collect(),
+            recent_blockhash: tx.message.recent_blockhash.to_string(),
+            instructions,
+        },
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 19. ASSOCIATED TOKEN ADDRESS ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct AtaRequest {
+    wallet: String,
+    mint: String,
+    token_program: Option<String>, // "spl-token" (default), "token-2022" or a program id
+    associated_token_program_id: Option<String>, // for forked SPL deployments
+}
+
+#[derive(Serialize)]
+struct AtaResponse {
+    address: String,
+    token_program: String,
+}
+
+async fn derive_ata(JsonBody(req): JsonBody<AtaRequest>) -> impl IntoResponse {
+    let wallet = match Pubkey::from_str(&req.wallet) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid wallet pubkey".into()))),
+    };
+
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let token_program = match parse_token_program(req.token_program.as_deref(), spl_token::id()) {
+        Ok(p) => p,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let ata_program = match parse_ata_program(req.associated_token_program_id.as_deref()) {
+        Ok(p) => p,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    // The token program id is one of the ATA seeds, so Token-2022 mints get a different address
+    let address = associated_token_address(&wallet, &mint, &token_program, &ata_program);
+
+    let response = AtaResponse {
+        address: address.to_string(),
+        token_program: token_program.to_string(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 20. ON-CHAIN VERIFIABLE SIGNATURE ENDPOINT =====
+
+#[derive(Serialize)]
+struct Ed25519Offsets {
+    signature_offset: u16,
+    signature_instruction_index: u16,
+    public_key_offset: u16,
+    public_key_instruction_index: u16,
+    message_data_offset: u16,
+    message_data_size: u16,
+    message_instruction_index: u16,
+}
+
+#[derive(Serialize)]
+struct SignOnchainResponse {
+    // Same fields as /message/sign, so the detached signature can be used on its own
+    #[serde(flatten)]
+    signed: SignMessageResponse,
+    instruction: InstructionJson,
+    offsets: Ed25519Offsets,
+}
+
+/// Builds an ed25519 native program instruction carrying one signature, laid out
+/// exactly like `solana_sdk::ed25519_instruction::new_ed25519_instruction`:
+/// a signature count and padding byte, the offsets, then pubkey, signature and message.
+fn ed25519_verify_instruction(
+    pubkey: &Pubkey,
+    signature: &Signature,
+    message: &[u8],
+) -> Result<(Instruction, Ed25519Offsets), String> {
+    let public_key_offset = DATA_START;
+    let signature_offset = public_key_offset + PUBKEY_SERIALIZED_SIZE;
+    let message_data_offset = signature_offset + SIGNATURE_SERIALIZED_SIZE;
+
+    if message_data_offset + message.len() > u16::MAX as usize {
+        return Err("Message is too long for an ed25519 instruction".to_string());
+    }
+
+    // u16::MAX instruction indexes point the program at this instruction's own data
+    let offsets = Ed25519Offsets {
+        signature_offset: signature_offset as u16,
+        signature_instruction_index: u16::MAX,
+        public_key_offset: public_key_offset as u16,
+        public_key_instruction_index: u16::MAX,
+        message_data_offset: message_data_offset as u16,
+        message_data_size: message.len() as u16,
+        message_instruction_index: u16::MAX,
+    };
+
+    let mut data = Vec::with_capacity(message_data_offset + message.len());
+    data.extend_from_slice(&[1, 0]); // one signature, plus a padding byte for alignment
+    for field in [
+        offsets.signature_offset,
+        offsets.signature_instruction_index,
+        offsets.public_key_offset,
+        offsets.public_key_instruction_index,
+        offsets.message_data_offset,
+        offsets.message_data_size,
+        offsets.message_instruction_index,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(pubkey.as_ref());
+    data.extend_from_slice(signature.as_ref());
+    data.extend_from_slice(message);
+
+    let instruction = Instruction {
+        program_id: solana_sdk::ed25519_program::id(),
+        accounts: vec![],
+        data,
+    };
+
+    Ok((instruction, offsets))
+}
+
+async fn sign_message_onchain(
+    Query(echo): Query<EchoParams>,
+    JsonBody(req): JsonBody<SignMessageRequest>,
+) -> impl IntoResponse {
+    if req.message.trim().is_empty() || req.secret.trim().is_empty() {
+        return Json(ApiResponse::from(Err("Missing required fields".into())));
+    }
+
+    let keypair = match parse_keypair(&req.secret) {
+        Ok(kp) => kp,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let signature = keypair.sign_message(req.message.as_bytes());
+
+    let (instruction, offsets) =
+        match ed25519_verify_instruction(&keypair.pubkey(), &signature, req.message.as_bytes()) {
+            Ok(built) => built,
+            Err(e) => return Json(ApiResponse::from(Err(e))),
+        };
+
+    let response = SignOnchainResponse {
+        signed: SignMessageResponse {
+            signature: base64::prelude::BASE64_STANDARD.encode(signature.as_ref()),
+            public_key: keypair.pubkey().to_string(),
+            message: echo.echo_message.then(|| req.message.clone()),
+        },
+        instruction: InstructionJson::from(instruction),
+        offsets,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 21. TOKEN SWEEP ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct SweepTokenRequest {
+    source: String,      // source token account (need not be an ATA)
+    mint: String,
+    destination: String, // destination wallet; tokens go to its associated token account
+    owner: String,       // owner of the source token account
+    amount: u64,         // should be the full balance when close_source is set
+    #[serde(default)]
+    close_source: bool,
+    rent_destination: Option<String>, // receives the source account's rent, defaults to owner
+}
+
+#[derive(Serialize)]
+struct SweepTokenResponse {
+    instructions: Vec<InstructionJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    combined_instructions_base64: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
+}
+
+async fn sweep_token(
+    Query(params): Query<CombinedParams>,
+    Query(guard): Query<AmountGuardParams>,
+    JsonBody(req): JsonBody<SweepTokenRequest>,
+) -> impl IntoResponse {
+    if req.amount == 0 {
+        return Json(ApiResponse::from(Err("Amount must be greater than 0".into())));
+    }
+
+    if let Err(e) = guard.check(req.amount) {
+        return Json(ApiResponse::from(Err(e)));
+    }
+
+    let source = match Pubkey::from_str(&req.source) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid source pubkey".into()))),
+    };
+
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let destination_wallet = match Pubkey::from_str(&req.destination) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid destination pubkey".into()))),
+    };
+
+    let owner = match Pubkey::from_str(&req.owner) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid owner pubkey".into()))),
+    };
+
+    let rent_destination = match req.rent_destination.as_deref() {
+        None => owner,
+        Some(value) => match Pubkey::from_str(value) {
+            Ok(p) => p,
+            Err(_) => return Json(ApiResponse::from(Err("Invalid rent_destination pubkey".into()))),
+        },
+    };
+
+    let destination_ata = get_associated_token_address(&destination_wallet, &mint);
+    if destination_ata == source {
+        return Json(ApiResponse::from(Err("Source and destination cannot be the same".into())));
+    }
+
+    let transfer_ix = match spl_transfer(
+        &spl_token::id(),
+        &source,
+        &destination_ata,
+        &owner,
+        &[], // no multisig signers
+        req.amount,
+    ) {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build transfer instruction: {e}")))),
+    };
+
+    let mut instructions = vec![transfer_ix];
+    let mut warning = None;
+
+    if req.close_source {
+        if rent_destination == source {
+            return Json(ApiResponse::from(Err("rent_destination cannot be the source account".into())));
+        }
+
+        let close_ix = match close_account(&spl_token::id(), &source, &rent_destination, &owner, &[]) {
+            Ok(ix) => ix,
+            Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build close instruction: {e}")))),
+        };
+        instructions.push(close_ix);
+
+        // Balances aren't visible here; the program rejects closing a non-empty account
+        warning = Some(
+            "close_account fails unless amount is the source account's entire balance".to_string(),
+        );
+    }
+
+    let combined_instructions_base64 = if params.combined {
+        match combine_instructions(&instructions) {
+            Ok(combined) => Some(combined),
+            Err(e) => return Json(ApiResponse::from(Err(e))),
+        }
+    } else {
+        None
+    };
+
+    let response = SweepTokenResponse {
+        instructions: instructions.into_iter().map(InstructionJson::from).collect(),
+        combined_instructions_base64,
+        warning,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 22. KEYPAIR WITH PROOF OF POSSESSION ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct KeypairProofRequest {
+    challenge: String, // nonce to bind the new key to
+}
+
+#[derive(Serialize)]
+struct KeypairProofResponse {
+    pubkey: String,
+    secret: String,
+    signature: String, // base64 signature over the challenge
+}
+
+async fn generate_keypair_with_proof(JsonBody(req): JsonBody<KeypairProofRequest>) -> impl IntoResponse {
+    if req.challenge.trim().is_empty() {
+        return Json(ApiResponse::from(Err("Missing required fields".into())));
+    }
+
+    let keypair = Keypair::new();
+    let signature = keypair.sign_message(req.challenge.as_bytes());
+
+    let response = KeypairProofResponse {
+        pubkey: keypair.pubkey().to_string(),
+        secret: bs58::encode(keypair.to_bytes()).into_string(),
+        signature: base64::prelude::BASE64_STANDARD.encode(signature.as_ref()),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 23. ENSURE WSOL ACCOUNT ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct EnsureWsolRequest {
+    owner: String, // wallet that will own the wSOL account
+    payer: String, // funds the account's rent if it has to be created
+}
+
+#[derive(Serialize)]
+struct EnsureWsolResponse {
+    address: String,
+    instruction: InstructionJson,
+}
+
+async fn ensure_wsol_account(JsonBody(req): JsonBody<EnsureWsolRequest>) -> impl IntoResponse {
+    let owner = match Pubkey::from_str(&req.owner) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid owner pubkey".into()))),
+    };
+
+    let payer = match Pubkey::from_str(&req.payer) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid payer pubkey".into()))),
+    };
+
+    let native_mint = spl_token::native_mint::id();
+    let address = get_associated_token_address(&owner, &native_mint);
+
+    // The idempotent variant succeeds even if the account already exists
+    let instruction =
+        create_associated_token_account_idempotent(&payer, &owner, &native_mint, &spl_token::id());
+
+    let response = EnsureWsolResponse {
+        address: address.to_string(),
+        instruction: InstructionJson::from(instruction),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 24. SET AUTHORITY ENDPOINT =====
+
+/// `target` depends on the authority type:
+/// - `mint_tokens`, `freeze_account`: the mint
+/// - `account_owner`, `close_account`: a token account
+#[derive(Debug, Deserialize)]
+struct SetAuthorityRequest {
+    target: String,
+    authority_type: String,
+    current_authority: String,
+    new_authority: Option<String>, // omit to remove the authority where allowed
+}
+
+fn parse_authority_type(value: &str) -> Result<AuthorityType, String> {
+    match value {
+        "mint_tokens" => Ok(AuthorityType::MintTokens),
+        "freeze_account" => Ok(AuthorityType::FreezeAccount),
+        "account_owner" => Ok(AuthorityType::AccountOwner),
+        "close_account" => Ok(AuthorityType::CloseAccount),
+        _ => Err(format!(
+            "Invalid authority_type: {value}, expected mint_tokens, freeze_account, account_owner or close_account"
+        )),
+    }
+}
+
+fn authority_type_name(authority_type: &AuthorityType) -> &'static str {
+    match authority_type {
+        AuthorityType::MintTokens => "mint_tokens",
+        AuthorityType::FreezeAccount => "freeze_account",
+        AuthorityType::AccountOwner => "account_owner",
+        AuthorityType::CloseAccount => "close_account",
+    }
+}
+
+async fn set_token_authority(JsonBody(req): JsonBody<SetAuthorityRequest>) -> impl IntoResponse {
+    let authority_type = match parse_authority_type(req.authority_type.trim()) {
+        Ok(t) => t,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let target = match Pubkey::from_str(&req.target) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid target pubkey".into()))),
+    };
+
+    let current_authority = match Pubkey::from_str(&req.current_authority) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid current_authority pubkey".into()))),
+    };
+
+    let new_authority = match req.new_authority.as_deref() {
+        None => None,
+        Some(value) => match Pubkey::from_str(value) {
+            Ok(p) => Some(p),
+            Err(_) => return Json(ApiResponse::from(Err("Invalid new_authority pubkey".into()))),
+        },
+    };
+
+    // A token account always needs an owner; the program rejects removing it
+    if authority_type == AuthorityType::AccountOwner && new_authority.is_none() {
+        return Json(ApiResponse::from(Err("account_owner requires a new_authority".into())));
+    }
+
+    let instruction = match set_authority(
+        &spl_token::id(),
+        &target,
+        new_authority.as_ref(),
+        authority_type,
+        &current_authority,
+        &[], // no multisig signers
+    ) {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build set_authority instruction: {e}")))),
+    };
+
+    Json(ApiResponse::from(Ok(InstructionJson::from(instruction))))
+}
+
+// ===== 25. SOL TRANSFER FEE ENDPOINT =====
+
+#[cfg(feature = "rpc")]
+#[derive(Debug, Deserialize)]
+struct TransferFeeRequest {
+    rpc_url: Option<String>,
+    cluster: Option<String>,
+}
+
+#[cfg(feature = "rpc")]
+#[derive(Serialize)]
+struct TransferFeeResponse {
+    lamports: BigUint,
+}
+
+#[cfg(feature = "rpc")]
+/// Fee for a plain one-signer SOL transfer at the current blockhash.
+async fn estimate_transfer_fee(client: &RpcClient) -> Result<u64, String> {
+    // getFeeForMessage only prices messages with a blockhash the cluster still knows
+    let blockhash = client
+        .get_latest_blockhash()
+        .await
+        .map_err(|e| format!("Failed to fetch blockhash: {e}"))?;
+
+    // Any two distinct accounts give the same canonical one-signer transfer
+    let from = Pubkey::new_unique();
+    let to = Pubkey::new_unique();
+    let message = Message::new_with_blockhash(&[transfer(&from, &to, 1)], Some(&from), &blockhash);
+
+    client
+        .get_fee_for_message(&message)
+        .await
+        .map_err(|e| format!("Failed to fetch fee: {e}"))
+}
+
+#[cfg(feature = "rpc")]
+async fn transfer_sol_fee(
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<TransferFeeRequest>,
+) -> impl IntoResponse {
+    let client = match rpc_client(req.rpc_url.as_deref(), req.cluster.as_deref()) {
+        Ok(c) => c,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let lamports = match estimate_transfer_fee(&client).await {
+        Ok(fee) => bigint.wrap(fee),
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    Json(ApiResponse::from(Ok(TransferFeeResponse { lamports })))
+}
+
+// ===== 26. TOKEN INSTRUCTION DECODE ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct DecodeTokenInstructionRequest {
+    data: String, // base64-encoded spl-token instruction data
+}
+
+/// Variant name plus its fields, e.g. `{ "type": "Transfer", "amount": 100 }`.
+fn describe_token_instruction(instruction: &TokenInstruction, bigint: &BigintParams) -> Value {
+    let optional_key = |key: &spl_token::solana_program::program_option::COption<Pubkey>| {
+        Option::<Pubkey>::from(*key).map(|k| k.to_string())
+    };
+
+    match instruction {
+        TokenInstruction::InitializeMint { decimals, mint_authority, freeze_authority } => json!({
+            "type": "InitializeMint",
+            "decimals": decimals,
+            "mint_authority": mint_authority.to_string(),
+            "freeze_authority": optional_key(freeze_authority),
+        }),
+        TokenInstruction::InitializeMint2 { decimals, mint_authority, freeze_authority } => json!({
+            "type": "InitializeMint2",
+            "decimals": decimals,
+            "mint_authority": mint_authority.to_string(),
+            "freeze_authority": optional_key(freeze_authority),
+        }),
+        TokenInstruction::InitializeAccount => json!({ "type": "InitializeAccount" }),
+        TokenInstruction::InitializeAccount2 { owner } => {
+            json!({ "type": "InitializeAccount2", "owner": owner.to_string() })
+        }
+        TokenInstruction::InitializeAccount3 { owner } => {
+            json!({ "type": "InitializeAccount3", "owner": owner.to_string() })
+        }
+        TokenInstruction::InitializeMultisig { m } => json!({ "type": "InitializeMultisig", "m": m }),
+        TokenInstruction::InitializeMultisig2 { m } => json!({ "type": "InitializeMultisig2", "m": m }),
+        TokenInstruction::Transfer { amount } => json!({ "type": "Transfer", "amount": bigint.wrap(*amount) }),
+        TokenInstruction::Approve { amount } => json!({ "type": "Approve", "amount": bigint.wrap(*amount) }),
+        TokenInstruction::Revoke => json!({ "type": "Revoke" }),
+        TokenInstruction::SetAuthority { authority_type, new_authority } => json!({
+            "type": "SetAuthority",
+            "authority_type": authority_type_name(authority_type),
+            "new_authority": optional_key(new_authority),
+        }),
+        TokenInstruction::MintTo { amount } => json!({ "type": "MintTo", "amount": bigint.wrap(*amount) }),
+        TokenInstruction::Burn { amount } => json!({ "type": "Burn", "amount": bigint.wrap(*amount) }),
+        TokenInstruction::CloseAccount => json!({ "type": "CloseAccount" }),
+        TokenInstruction::FreezeAccount => json!({ "type": "FreezeAccount" }),
+        TokenInstruction::ThawAccount => json!({ "type": "ThawAccount" }),
+        TokenInstruction::TransferChecked { amount, decimals } => {
+            json!({ "type": "TransferChecked", "amount": bigint.wrap(*amount), "decimals": decimals })
+        }
+        TokenInstruction::ApproveChecked { amount, decimals } => {
+            json!({ "type": "ApproveChecked", "amount": bigint.wrap(*amount), "decimals": decimals })
+        }
+        TokenInstruction::MintToChecked { amount, decimals } => {
+            json!({ "type": "MintToChecked", "amount": bigint.wrap(*amount), "decimals": decimals })
+        }
+        TokenInstruction::BurnChecked { amount, decimals } => {
+            json!({ "type": "BurnChecked", "amount": bigint.wrap(*amount), "decimals": decimals })
+        }
+        TokenInstruction::SyncNative => json!({ "type": "SyncNative" }),
+        TokenInstruction::GetAccountDataSize => json!({ "type": "GetAccountDataSize" }),
+        TokenInstruction::InitializeImmutableOwner => json!({ "type": "InitializeImmutableOwner" }),
+        TokenInstruction::AmountToUiAmount { amount } => {
+            json!({ "type": "AmountToUiAmount", "amount": bigint.wrap(*amount) })
+        }
+        TokenInstruction::UiAmountToAmount { ui_amount } => {
+            json!({ "type": "UiAmountToAmount", "ui_amount": ui_amount })
+        }
+    }
+}
+
+async fn decode_token_instruction(
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<DecodeTokenInstructionRequest>,
+) -> impl IntoResponse {
+    let data = match base64::prelude::BASE64_STANDARD.decode(req.data.trim()) {
+        Ok(bytes) => bytes,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid base64 data".into()))),
+    };
+
+    let instruction = match TokenInstruction::unpack(&data) {
+        Ok(ix) => ix,
+        Err(_) => return Json(ApiResponse::from(Err("Data is not a valid spl-token instruction".into()))),
+    };
+
+    Json(ApiResponse::from(Ok(describe_token_instruction(&instruction, &bigint))))
+}
+
+// ===== 27. SIGN-IN WITH SOLANA ENDPOINTS =====
+
+/// The message fields shared by the sign and verify requests. They are listed
+/// in each request rather than flattened, since serde buffers flattened content
+/// and `?strict_fields=true` would no longer see unknown keys.
+struct SiwsFields<'a> {
+    domain: &'a str,
+    statement: Option<&'a str>,
+    nonce: &'a str,     // at least 8 alphanumeric characters
+    issued_at: &'a str, // RFC 3339 timestamp, e.g. 2024-01-01T00:00:00Z
+}
+
+#[derive(Debug, Deserialize)]
+struct SiwsSignRequest {
+    secret: String,
+    domain: String,
+    statement: Option<String>,
+    nonce: String,
+    issued_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SiwsVerifyRequest {
+    pubkey: String,
+    signature: String, // base64-encoded signature
+    domain: String,
+    statement: Option<String>,
+    nonce: String,
+    issued_at: String,
+}
+
+#[derive(Serialize)]
+struct SiwsSignResponse {
+    signature: String,
+    public_key: String,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct SiwsVerifyResponse {
+    valid: bool,
+    message: String,
+}
+
+/// Builds the canonical Sign-In With Solana message for `address`. The domain
+/// prefix binds the signature to one site, so it can't be replayed elsewhere.
+fn siws_message(fields: SiwsFields<'_>, address: &Pubkey) -> Result<String, String> {
+    let domain = fields.domain.trim();
+    if domain.is_empty() || domain.contains(char::is_whitespace) {
+        return Err("Invalid domain".to_string());
+    }
+    if fields.nonce.len() < 8 || !fields.nonce.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("Nonce must be at least 8 alphanumeric characters".to_string());
+    }
+    if fields.issued_at.trim().is_empty() {
+        return Err("Missing required fields".to_string());
+    }
+    // SIWS requires an RFC 3339 timestamp; the caller's spelling is kept so the
+    // rebuilt message matches what the wallet signed byte for byte
+    if chrono::DateTime::parse_from_rfc3339(fields.issued_at.trim()).is_err() {
+        return Err("issued_at must be an RFC 3339 timestamp, e.g. 2024-01-01T00:00:00Z".to_string());
+    }
+
+    let mut message = format!("{domain} wants you to sign in with your Solana account:\n{address}");
+    if let Some(statement) = fields.statement.filter(|s| !s.is_empty()) {
+        if statement.contains('\n') {
+            return Err("Statement must be a single line".to_string());
+        }
+        message.push_str(&format!("\n\n{statement}"));
+    }
+    message.push_str(&format!("\n\nNonce: {}\nIssued At: {}", fields.nonce, fields.issued_at.trim()));
+
+    Ok(message)
+}
+
+async fn sign_siws_message(JsonBody(req): JsonBody<SiwsSignRequest>) -> impl IntoResponse {
+    let keypair = match parse_keypair(&req.secret) {
+        Ok(kp) => kp,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let fields = SiwsFields {
+        domain: &req.domain,
+        statement: req.statement.as_deref(),
+        nonce: &req.nonce,
+        issued_at: &req.issued_at,
+    };
+    let message = match siws_message(fields, &keypair.pubkey()) {
+        Ok(m) => m,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let signature = keypair.sign_message(message.as_bytes());
+
+    let response = SiwsSignResponse {
+        signature: base64::prelude::BASE64_STANDARD.encode(signature.as_ref()),
+        public_key: keypair.pubkey().to_string(),
+        message,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+async fn verify_siws_message(JsonBody(req): JsonBody<SiwsVerifyRequest>) -> impl IntoResponse {
+    let pubkey = match Pubkey::from_str(&req.pubkey) {
+        Ok(pk) => pk,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid public key".into()))),
+    };
+
+    let signature = match base64::prelude::BASE64_STANDARD.decode(&req.signature) {
+        Ok(bytes) if bytes.len() == 64 => match Signature::try_from(bytes.as_slice()) {
+            Ok(sig) => sig,
+            Err(_) => return Json(ApiResponse::from(Err("Failed to parse signature".into()))),
+        },
+        _ => return Json(ApiResponse::from(Err("Invalid signature format".into()))),
+    };
+
+    // Rebuild the exact message the wallet should have signed
+    let fields = SiwsFields {
+        domain: &req.domain,
+        statement: req.statement.as_deref(),
+        nonce: &req.nonce,
+        issued_at: &req.issued_at,
+    };
+    let message = match siws_message(fields, &pubkey) {
+        Ok(m) => m,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let response = SiwsVerifyResponse {
+        valid: signature.verify(&pubkey.to_bytes(), message.as_bytes()),
+        message,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 28. BASE58 NORMALIZE ENDPOINT =====
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+#[derive(Debug, Deserialize)]
+struct NormalizeBase58Request {
+    input: String,
+}
+
+#[derive(Serialize)]
+struct NormalizeBase58Response {
+    normalized: String,
+    byte_length: usize,
+    changed: bool,
+}
+
+async fn normalize_base58(JsonBody(req): JsonBody<NormalizeBase58Request>) -> impl IntoResponse {
+    let trimmed = req.input.trim();
+    if trimmed.is_empty() {
+        return Json(ApiResponse::from(Err("Missing required fields".into())));
+    }
+
+    // Report the first offending character so copy-paste mistakes are easy to spot
+    if let Some((index, c)) = trimmed.chars().enumerate().find(|(_, c)| !BASE58_ALPHABET.contains(*c)) {
+        return Json(ApiResponse::from(Err(format!(
+            "Invalid base58 character '{c}' at index {index}"
+        ))));
+    }
+
+    let bytes = match bs58::decode(trimmed).into_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Invalid base58: {e}")))),
+    };
+
+    let normalized = bs58::encode(&bytes).into_string();
+
+    let response = NormalizeBase58Response {
+        changed: normalized != req.input,
+        byte_length: bytes.len(),
+        normalized,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 29. COMBINED INSTRUCTIONS DECODE ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct DecodeCombinedRequest {
+    combined: String, // combined_instructions_base64 from a multi-instruction endpoint
+}
+
+async fn decode_combined_instructions(JsonBody(req): JsonBody<DecodeCombinedRequest>) -> impl IntoResponse {
+    let instructions = match split_combined_instructions(req.combined.trim()) {
+        Ok(ixs) => ixs,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let response: Vec<InstructionJson> = instructions.into_iter().map(InstructionJson::from).collect();
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 30. SIGNED AUTH TOKEN ENDPOINTS =====
+
+const MAX_AUTH_TOKEN_TTL_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AuthClaims {
+    pubkey: String,
+    exp: u64, // unix seconds
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthTokenRequest {
+    secret: String,
+    ttl_secs: u64,
+}
+
+#[derive(Serialize)]
+struct AuthTokenResponse {
+    token: String,
+    pubkey: String,
+    exp: BigUint,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthVerifyRequest {
+    token: String,
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Checks a `<base64url payload>.<base64url signature>` token: the signature must be
+/// made by the payload's own pubkey over the payload bytes, and `exp` must be in the future.
+fn verify_auth_token(token: &str) -> Result<AuthClaims, String> {
+    let (payload_b64, signature_b64) = token.trim().split_once('.').ok_or("Malformed token")?;
+
+    let payload = base64::prelude::BASE64_URL_SAFE_NO_PAD
+        .decode(payload_b64)
+        .map_err(|_| "Malformed token payload")?;
+    let claims: AuthClaims =
+        serde_json::from_slice(&payload).map_err(|_| "Malformed token payload")?;
+
+    let pubkey = Pubkey::from_str(&claims.pubkey).map_err(|_| "Invalid token pubkey")?;
+    let signature = base64::prelude::BASE64_URL_SAFE_NO_PAD
+        .decode(signature_b64)
+        .ok()
+        .and_then(|bytes| Signature::try_from(bytes.as_slice()).ok())
+        .ok_or("Malformed token signature")?;
+
+    if !signature.verify(&pubkey.to_bytes(), &payload) {
+        return Err("Invalid token signature".to_string());
+    }
+    if claims.exp <= unix_now() {
+        return Err("Token expired".to_string());
+    }
+
+    Ok(claims)
+}
+
+async fn issue_auth_token(
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<AuthTokenRequest>,
+) -> impl IntoResponse {
+    if req.ttl_secs == 0 || req.ttl_secs > MAX_AUTH_TOKEN_TTL_SECS {
+        return Json(ApiResponse::from(Err(format!(
+            "ttl_secs must be between 1 and {MAX_AUTH_TOKEN_TTL_SECS}"
+        ))));
+    }
+
+    let keypair = match parse_keypair(&req.secret) {
+        Ok(kp) => kp,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let claims = AuthClaims {
+        pubkey: keypair.pubkey().to_string(),
+        exp: unix_now() + req.ttl_secs,
+    };
+
+    let payload = match serde_json::to_vec(&claims) {
+        Ok(bytes) => bytes,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to encode token: {e}")))),
+    };
+    let signature = keypair.sign_message(&payload);
+
+    let token = format!(
+        "{}.{}",
+        base64::prelude::BASE64_URL_SAFE_NO_PAD.encode(&payload),
+        base64::prelude::BASE64_URL_SAFE_NO_PAD.encode(signature.as_ref())
+    );
+
+    let response = AuthTokenResponse {
+        token,
+        pubkey: claims.pubkey,
+        exp: bigint.wrap(claims.exp),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+async fn verify_auth_token_handler(JsonBody(req): JsonBody<AuthVerifyRequest>) -> impl IntoResponse {
+    Json(ApiResponse::from(verify_auth_token(&req.token)))
+}
+
+/// Rejects POST requests without a valid bearer token from an allowlisted pubkey.
+/// Token issuing/verification itself stays open so clients can log in.
+async fn require_bearer_auth(
+    State(authorized): State<Arc<HashSet<Pubkey>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.method() != Method::POST || request.uri().path().starts_with("/auth/") {
+        return next.run(request).await;
+    }
+
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    let result = match token {
+        Some(token) => verify_auth_token(token).and_then(|claims| {
+            let pubkey = Pubkey::from_str(&claims.pubkey).map_err(|_| "Invalid token pubkey")?;
+            if authorized.contains(&pubkey) {
+                Ok(())
+            } else {
+                Err("Pubkey not authorized".to_string())
+            }
+        }),
+        None => Err("Missing bearer token".to_string()),
+    };
+
+    match result {
+        Ok(()) => next.run(request).await,
+        Err(e) => (
+            StatusCode::UNAUTHORIZED,
+            Json(ApiResponse::<()>::from(Err(e))),
+        )
+            .into_response(),
+    }
+}
+
+// ===== 31. MEMO TRANSACTION ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct MemoTxRequest {
+    payer_secret: String,
+    memo: String,
+    recent_blockhash: String,
+}
+
+#[derive(Serialize)]
+struct MemoTxResponse {
+    transaction: String, // signed transaction, base64 unless ?tx_encoding=base58
+    signature: String,
+    size: usize,
+}
+
+async fn build_memo_transaction(
+    Query(encoding): Query<TxEncodingParams>,
+    JsonBody(req): JsonBody<MemoTxRequest>,
+) -> impl IntoResponse {
+    if req.payer_secret.trim().is_empty() || req.memo.is_empty() || req.recent_blockhash.trim().is_empty() {
+        return Json(ApiResponse::from(Err("Missing required fields".into())));
+    }
+
+    let payer = match parse_keypair(&req.payer_secret) {
+        Ok(kp) => kp,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let recent_blockhash = match Hash::from_str(req.recent_blockhash.trim()) {
+        Ok(hash) => hash,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid recent_blockhash".into()))),
+    };
+
+    // The payer signs the memo as well, so the anchored data is attributable to it
+    let memo = spl_memo::build_memo(req.memo.as_bytes(), &[&payer.pubkey()]);
+    let tx = Transaction::new_signed_with_payer(&[memo], Some(&payer.pubkey()), &[&payer], recent_blockhash);
+
+    let bytes = match bincode::serialize(&tx) {
+        Ok(bytes) => bytes,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to serialize transaction: {e}")))),
+    };
+
+    // Everything except the memo bytes and their compact-u16 length prefix is fixed,
+    // so the packet limit is a memo length limit
+    if bytes.len() > PACKET_DATA_SIZE {
+        let fixed = bytes.len() - req.memo.len() - short_vec_len(req.memo.len());
+        let budget = PACKET_DATA_SIZE.saturating_sub(fixed);
+        let max_memo = (0..=budget)
+            .rev()
+            .find(|len| len + short_vec_len(*len) <= budget)
+            .unwrap_or_default();
+        return Json(ApiResponse::from(Err(format!(
+            "Memo too long: {} bytes, at most {max_memo} fit in one transaction",
+            req.memo.len()
+        ))));
+    }
+
+    let response = MemoTxResponse {
+        transaction: encoding.tx_encoding.encode(&bytes),
+        signature: tx.signatures[0].to_string(),
+        size: bytes.len(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 32. TRANSACTION STATUS ENDPOINT =====
+
+#[cfg(feature = "rpc")]
+#[derive(Debug, Deserialize)]
+struct TxStatusRequest {
+    signature: String,
+    rpc_url: Option<String>,
+    cluster: Option<String>,
+}
+
+#[cfg(feature = "rpc")]
+#[derive(Serialize)]
+struct TxStatusResponse {
+    found: bool,
+    confirmed: bool,
+    confirmation_status: Option<Value>, // "processed", "confirmed" or "finalized"
+    err: Option<String>,
+    slot: Option<BigUint>,
+}
+
+#[cfg(feature = "rpc")]
+async fn transaction_status(
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<TxStatusRequest>,
+) -> impl IntoResponse {
+    if req.signature.trim().is_empty() {
+        return Json(ApiResponse::from(Err("Missing required fields".into())));
+    }
+
+    let signature = match Signature::from_str(req.signature.trim()) {
+        Ok(sig) => sig,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid signature".into()))),
+    };
+
+    let client = match rpc_client(req.rpc_url.as_deref(), req.cluster.as_deref()) {
+        Ok(c) => c,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    // Searching history too means "not found" really means unknown, not just
+    // older than the node's recent status cache
+    let status = match client.get_signature_statuses_with_history(&[signature]).await {
+        Ok(response) => response.value.into_iter().next().flatten(),
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to fetch signature status: {e}")))),
+    };
+
+    // Not landed (yet): report it as data so pollers can simply retry
+    let Some(status) = status else {
+        let response = TxStatusResponse {
+            found: false,
+            confirmed: false,
+            confirmation_status: None,
+            err: None,
+            slot: None,
+        };
+        return Json(ApiResponse::from(Ok(response)));
+    };
+
+    let response = TxStatusResponse {
+        found: true,
+        confirmed: status.satisfies_commitment(CommitmentConfig::confirmed()),
+        confirmation_status: serde_json::to_value(status.confirmation_status()).ok(),
+        err: status.err.as_ref().map(|e| e.to_string()),
+        slot: Some(bigint.wrap(status.slot)),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 33. MULTISIG CREATION ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct CreateMultisigRequest {
+    payer: String,
+    multisig: String, // new account, must also sign the create_account instruction
+    signers: Vec<String>,
+    m: u8,
+}
+
+#[derive(Serialize)]
+struct CreateMultisigResponse {
+    instructions: Vec<InstructionJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    combined_instructions_base64: Option<String>,
+    space: usize,
+    lamports: BigUint,
+}
+
+async fn create_multisig_full(
+    Query(params): Query<CombinedParams>,
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<CreateMultisigRequest>,
+) -> impl IntoResponse {
+    let payer = match Pubkey::from_str(&req.payer) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid payer pubkey".into()))),
+    };
+
+    let multisig = match Pubkey::from_str(&req.multisig) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid multisig pubkey".into()))),
+    };
+
+    let mut signers = Vec::with_capacity(req.signers.len());
+    for (i, signer) in req.signers.iter().enumerate() {
+        match Pubkey::from_str(signer) {
+            Ok(p) if signers.contains(&p) => {
+                return Json(ApiResponse::from(Err(format!("Duplicate signer at index {i}"))));
+            }
+            Ok(p) => signers.push(p),
+            Err(_) => return Json(ApiResponse::from(Err(format!("Invalid signer pubkey at index {i}")))),
+        }
+    }
+
+    if signers.is_empty() || signers.len() > spl_token::instruction::MAX_SIGNERS {
+        return Json(ApiResponse::from(Err(format!(
+            "signers must contain between 1 and {} pubkeys",
+            spl_token::instruction::MAX_SIGNERS
+        ))));
+    }
+    if req.m == 0 || req.m as usize > signers.len() {
+        return Json(ApiResponse::from(Err("m must be between 1 and the number of signers".into())));
+    }
+
+    let space = spl_token::state::Multisig::LEN;
+    let lamports = Rent::default().minimum_balance(space);
+
+    let signer_refs: Vec<&Pubkey> = signers.iter().collect();
+    let initialize = match initialize_multisig(&spl_token::id(), &multisig, &signer_refs, req.m) {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build initialize_multisig instruction: {e}")))),
+    };
+
+    // The account has to exist, rent-exempt and owned by the token program, before it can be initialized
+    let instructions = vec![
+        create_account(&payer, &multisig, lamports, space as u64, &spl_token::id()),
+        initialize,
+    ];
+
+    let combined_instructions_base64 = if params.combined {
+        match combine_instructions(&instructions) {
+            Ok(combined) => Some(combined),
+            Err(e) => return Json(ApiResponse::from(Err(e))),
+        }
+    } else {
+        None
+    };
+
+    let response = CreateMultisigResponse {
+        instructions: instructions.into_iter().map(InstructionJson::from).collect(),
+        combined_instructions_base64,
+        space,
+        lamports: bigint.wrap(lamports),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 34. ATA RELATIONSHIP CHECK ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct AtaRelationshipRequest {
+    token_account: String,
+    wallet: String,
+    mint: String,
+    token_program: Option<String>, // "spl-token" (default), "token-2022" or a program id
+    associated_token_program_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AtaRelationshipResponse {
+    matches: bool,
+    expected_address: String,
+    token_program: String,
+    // Set when the account is this wallet's ATA, but under the other standard token program
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matches_program: Option<String>,
+}
+
+async fn check_ata_relationship(JsonBody(req): JsonBody<AtaRelationshipRequest>) -> impl IntoResponse {
+    let token_account = match Pubkey::from_str(&req.token_account) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid token_account pubkey".into()))),
+    };
+
+    let wallet = match Pubkey::from_str(&req.wallet) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid wallet pubkey".into()))),
+    };
+
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let token_program = match parse_token_program(req.token_program.as_deref(), spl_token::id()) {
+        Ok(p) => p,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let ata_program = match parse_ata_program(req.associated_token_program_id.as_deref()) {
+        Ok(p) => p,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let expected = associated_token_address(&wallet, &mint, &token_program, &ata_program);
+    let matches = expected == token_account;
+
+    let matches_program = if matches {
+        None
+    } else {
+        [spl_token::id(), spl_token_2022::id()]
+            .into_iter()
+            .filter(|program| *program != token_program)
+            .find(|program| {
+                associated_token_address(&wallet, &mint, program, &ata_program) == token_account
+            })
+            .map(|program| program.to_string())
+    };
+
+    let response = AtaRelationshipResponse {
+        matches,
+        expected_address: expected.to_string(),
+        token_program: token_program.to_string(),
+        matches_program,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 35. TRANSFER CHECKED WITH FEE ENDPOINT (TOKEN-2022) =====
+
+#[derive(Debug, Deserialize)]
+struct TransferWithFeeRequest {
+    source: String,      // source token account
+    mint: String,
+    destination: String, // destination token account
+    owner: String,       // owner or delegate of the source account
+    amount: u64,
+    decimals: u8,
+    fee: u64,
+    // The mint's current transfer fee config; when given, `fee` is checked against it
+    transfer_fee_basis_points: Option<u16>,
+    maximum_fee: Option<u64>,
+}
+
+async fn transfer_checked_with_fee(
+    Query(guard): Query<AmountGuardParams>,
+    JsonBody(req): JsonBody<TransferWithFeeRequest>,
+) -> impl IntoResponse {
+    if req.amount == 0 {
+        return Json(ApiResponse::from(Err("Amount must be greater than 0".into())));
+    }
+
+    if let Err(e) = guard.check(req.amount) {
+        return Json(ApiResponse::from(Err(e)));
+    }
+    if req.fee > req.amount {
+        return Json(ApiResponse::from(Err("Fee cannot exceed the transfer amount".into())));
+    }
+
+    let source = match Pubkey::from_str(&req.source) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid source pubkey".into()))),
+    };
+
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let destination = match Pubkey::from_str(&req.destination) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid destination pubkey".into()))),
+    };
+
+    let owner = match Pubkey::from_str(&req.owner) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid owner pubkey".into()))),
+    };
+
+    if source == destination {
+        return Json(ApiResponse::from(Err("Source and destination cannot be the same".into())));
+    }
+
+    // The program rejects the transfer unless `fee` equals what the mint would charge
+    if let Some(basis_points) = req.transfer_fee_basis_points {
+        if basis_points > 10_000 {
+            return Json(ApiResponse::from(Err("transfer_fee_basis_points cannot exceed 10000".into())));
+        }
+
+        let transfer_fee = TransferFee {
+            transfer_fee_basis_points: basis_points.into(),
+            maximum_fee: req.maximum_fee.unwrap_or(u64::MAX).into(),
+            ..Default::default()
+        };
+        match transfer_fee.calculate_fee(req.amount) {
+            Some(expected) if expected == req.fee => {}
+            Some(expected) => {
+                return Json(ApiResponse::from(Err(format!(
+                    "Fee does not match the mint's transfer fee: expected {expected}, got {}",
+                    req.fee
+                ))));
+            }
+            None => return Json(ApiResponse::from(Err("Failed to calculate the transfer fee".into()))),
+        }
+    }
+
+    let instruction = match spl_token_2022::extension::transfer_fee::instruction::transfer_checked_with_fee(
+        &spl_token_2022::id(),
+        &source,
+        &mint,
+        &destination,
+        &owner,
+        &[], // no multisig signers
+        req.amount,
+        req.decimals,
+        req.fee,
+    ) {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build transfer instruction: {e}")))),
+    };
+
+    Json(ApiResponse::from(Ok(InstructionJson::from(instruction))))
+}
+
+// ===== 36. WITHDRAW WITHHELD TRANSFER FEES ENDPOINT (TOKEN-2022) =====
+
+// Each source costs 33 bytes of transaction space; 26 still fit next to a
+// separate fee payer and a compute budget instruction.
+const MAX_WITHDRAW_SOURCES: usize = 26;
+
+#[derive(Debug, Deserialize)]
+struct WithdrawWithheldRequest {
+    mint: String,
+    destination: String,        // token account receiving the harvested fees
+    withdraw_authority: String, // the mint's withdraw_withheld_authority
+    sources: Vec<String>,       // token accounts holding withheld fees
+}
+
+async fn withdraw_withheld_fees(
+    State(state): State<Arc<AppState>>,
+    JsonBody(req): JsonBody<WithdrawWithheldRequest>,
+) -> impl IntoResponse {
+    let max_sources = state.limits.withdraw_sources;
+    if req.sources.is_empty() || req.sources.len() > max_sources {
+        return Json(ApiResponse::from(Err(format!(
+            "sources must contain between 1 and {max_sources} token accounts"
+        ))));
+    }
+
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let destination = match Pubkey::from_str(&req.destination) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid destination pubkey".into()))),
+    };
+
+    let withdraw_authority = match Pubkey::from_str(&req.withdraw_authority) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid withdraw_authority pubkey".into()))),
+    };
+
+    let mut sources = Vec::with_capacity(req.sources.len());
+    for (i, source) in req.sources.iter().enumerate() {
+        match Pubkey::from_str(source) {
+            Ok(p) if sources.contains(&p) => {
+                return Json(ApiResponse::from(Err(format!("Duplicate source at index {i}"))));
+            }
+            Ok(p) => sources.push(p),
+            Err(_) => return Json(ApiResponse::from(Err(format!("Invalid source pubkey at index {i}")))),
+        }
+    }
+
+    let source_refs: Vec<&Pubkey> = sources.iter().collect();
+    let instruction = match spl_token_2022::extension::transfer_fee::instruction::withdraw_withheld_tokens_from_accounts(
+        &spl_token_2022::id(),
+        &mint,
+        &destination,
+        &withdraw_authority,
+        &[], // no multisig signers
+        &source_refs,
+    ) {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build withdraw instruction: {e}")))),
+    };
+
+    Json(ApiResponse::from(Ok(InstructionJson::from(instruction))))
+}
+
+// ===== 37. ATA FOR BOTH TOKEN PROGRAMS ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct AtaBothRequest {
+    wallet: String,
+    mint: String,
+    associated_token_program_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AtaBothResponse {
+    spl_token_ata: String,
+    token_2022_ata: String,
+}
+
+async fn derive_ata_both(JsonBody(req): JsonBody<AtaBothRequest>) -> impl IntoResponse {
+    let wallet = match Pubkey::from_str(&req.wallet) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid wallet pubkey".into()))),
+    };
+
+    let mint = match Pubkey::from_str(&req.mint) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let ata_program = match parse_ata_program(req.associated_token_program_id.as_deref()) {
+        Ok(p) => p,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let response = AtaBothResponse {
+        spl_token_ata: associated_token_address(&wallet, &mint, &spl_token::id(), &ata_program).to_string(),
+        token_2022_ata: associated_token_address(&wallet, &mint, &spl_token_2022::id(), &ata_program).to_string(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 38. INSTRUCTION ENCODE ENDPOINT =====
+
+#[derive(Serialize)]
+struct EncodeInstructionResponse {
+    encoded: String, // base64 of the bincode-serialized instruction
+    length: usize,
+}
+
+/// Takes the usual instruction JSON (`data` is accepted for `instruction_data`)
+/// and returns the same bincode layout the combined-instructions blob uses per entry.
+async fn encode_instruction(JsonBody(req): JsonBody<InstructionJson>) -> impl IntoResponse {
+    let instruction = match req.to_instruction() {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let bytes = match bincode::serialize(&instruction) {
+        Ok(bytes) => bytes,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to serialize instruction: {e}")))),
+    };
+
+    let response = EncodeInstructionResponse {
+        encoded: base64::prelude::BASE64_STANDARD.encode(&bytes),
+        length: bytes.len(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 39. MNEMONIC KEYPAIR DERIVATION ENDPOINT =====
+
+const DEFAULT_MAX_DERIVE_COUNT: usize = 100;
+
+// BIP44 path indices are hardened, so they must stay below 2^31
+const MAX_DERIVATION_INDEX: u32 = (1 << 31) - 1;
+
+#[derive(Debug, Deserialize)]
+struct DeriveManyRequest {
+    mnemonic: String,
+    count: u32,
+    #[serde(default)]
+    start_index: u32,
+}
+
+#[derive(Serialize)]
+struct DerivedKeypair {
+    index: u32,
+    path: String,
+    pubkey: String,
+    secret: String,
+}
+
+/// BIP39 seed for an English mnemonic, with the checksum verified (no passphrase).
+fn mnemonic_seed(phrase: &str) -> Result<bip39::Seed, String> {
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mnemonic = bip39::Mnemonic::from_phrase(&phrase, bip39::Language::English)
+        .map_err(|e| format!("Invalid mnemonic: {e}"))?;
+
+    Ok(bip39::Seed::new(&mnemonic, ""))
+}
+
+/// Derives the keypair at `m/44'/501'/{index}'/0'`, the path most Solana wallets use.
+fn derive_keypair(seed: &bip39::Seed, index: u32) -> Result<(Keypair, String), String> {
+    let path = DerivationPath::new_bip44(Some(index), Some(0));
+    let keypair = keypair_from_seed_and_derivation_path(seed.as_bytes(), Some(path))
+        .map_err(|e| format!("Failed to derive keypair: {e}"))?;
+
+    Ok((keypair, format!("m/44'/501'/{index}'/0'")))
+}
+
+async fn derive_many_keypairs(
+    State(state): State<Arc<AppState>>,
+    JsonBody(req): JsonBody<DeriveManyRequest>,
+) -> impl IntoResponse {
+    let max_count = state.limits.derive_keypairs;
+    if req.count == 0 || req.count as usize > max_count {
+        return Json(ApiResponse::from(Err(format!(
+            "count must be between 1 and {max_count}"
+        ))));
+    }
+
+    let last_index = match req.start_index.checked_add(req.count - 1) {
+        Some(last) if last <= MAX_DERIVATION_INDEX => last,
+        _ => return Json(ApiResponse::from(Err("Derivation index out of range".into()))),
+    };
+
+    let seed = match mnemonic_seed(&req.mnemonic) {
+        Ok(seed) => seed,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let mut keypairs = Vec::with_capacity(req.count as usize);
+    for index in req.start_index..=last_index {
+        let (keypair, path) = match derive_keypair(&seed, index) {
+            Ok(derived) => derived,
+            Err(e) => return Json(ApiResponse::from(Err(e))),
+        };
+
+        keypairs.push(DerivedKeypair {
+            index,
+            path,
+            pubkey: keypair.pubkey().to_string(),
+            secret: bs58::encode(keypair.to_bytes()).into_string(),
+        });
+    }
+
+    Json(ApiResponse::from(Ok(keypairs)))
+}
+
+// ===== 40. SOL TRANSFER RENT SAFETY CHECK ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct SafeCheckRequest {
+    from_balance: u64,
+    lamports: u64,
+    data_len: Option<usize>, // size of the source account's data, 0 for plain wallets
+}
+
+#[derive(Serialize)]
+struct SafeCheckResponse {
+    safe: bool,
+    remaining: BigUint,
+    rent_minimum: BigUint,
+}
+
+async fn send_sol_safe_check(
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<SafeCheckRequest>,
+) -> impl IntoResponse {
+    if req.lamports == 0 {
+        return Json(ApiResponse::from(Err("Amount must be greater than 0".into())));
+    }
+
+    let Some(remaining) = req.from_balance.checked_sub(req.lamports) else {
+        return Json(ApiResponse::from(Err("Insufficient balance".into())));
+    };
+
+    let rent_minimum = Rent::default().minimum_balance(req.data_len.unwrap_or(0));
+
+    // Emptying the account entirely is allowed; only a non-zero balance below
+    // the rent-exempt minimum is rejected by the runtime
+    let safe = remaining == 0 || remaining >= rent_minimum;
+
+    let response = SafeCheckResponse {
+        safe,
+        remaining: bigint.wrap(remaining),
+        rent_minimum: bigint.wrap(rent_minimum),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 41. NEW KEYPAIR WITH MNEMONIC ENDPOINT =====
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct NewMnemonicRequest {
+    word_count: Option<usize>, // 12 (default) or 24
+}
+
+#[derive(Serialize)]
+struct NewMnemonicResponse {
+    mnemonic: String,
+    pubkey: String,
+    secret: String,
+}
+
+/// True when the request carries no body at all, as with a bare `curl -X POST`.
+fn request_body_is_empty(headers: &header::HeaderMap) -> bool {
+    match headers.get(header::CONTENT_LENGTH) {
+        Some(len) => len.as_bytes() == b"0",
+        None => !headers.contains_key(header::TRANSFER_ENCODING),
+    }
+}
+
+async fn generate_keypair_with_mnemonic(
+    headers: header::HeaderMap,
+    body: Result<JsonBody<NewMnemonicRequest>, Response>,
+) -> Response {
+    let req = match body {
+        Ok(JsonBody(req)) => req,
+        // An empty body means "all defaults"; anything else that fails to parse is
+        // an error, never a silent fallback to a 12-word phrase
+        Err(_) if request_body_is_empty(&headers) => NewMnemonicRequest::default(),
+        // Strict-field rejections are already an ApiResponse
+        Err(rejection)
+            if rejection
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .is_some_and(|value| value == "application/json") =>
+        {
+            return rejection;
+        }
+        Err(rejection) => {
+            let status = rejection.status();
+            let body = axum::body::to_bytes(rejection.into_body(), usize::MAX).await.unwrap_or_default();
+            let message = String::from_utf8_lossy(&body).into_owned();
+            return (status, Json(ApiResponse::<()>::from(Err(message)))).into_response();
+        }
+    };
+
+    generate_mnemonic_keypair(req).into_response()
+}
+
+fn generate_mnemonic_keypair(req: NewMnemonicRequest) -> Json<ApiResponse<NewMnemonicResponse>> {
+    let mnemonic_type = match req.word_count.unwrap_or(12) {
+        12 => bip39::MnemonicType::Words12,
+        24 => bip39::MnemonicType::Words24,
+        _ => return Json(ApiResponse::from(Err("word_count must be 12 or 24".into()))),
+    };
+
+    let mnemonic = bip39::Mnemonic::new(mnemonic_type, bip39::Language::English);
+    let seed = bip39::Seed::new(&mnemonic, "");
+
+    // Same default path as /keypair/derive-many index 0, so wallets importing the phrase agree
+    let (keypair, _) = match derive_keypair(&seed, 0) {
+        Ok(derived) => derived,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let response = NewMnemonicResponse {
+        mnemonic: mnemonic.into_phrase(),
+        pubkey: keypair.pubkey().to_string(),
+        secret: bs58::encode(keypair.to_bytes()).into_string(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 42. DERIVATION PATH VALIDATION ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct DerivationPathRequest {
+    path: String, // e.g. m/44'/501'/0'/0'
+}
+
+#[derive(Serialize)]
+struct DerivationPathResponse {
+    valid: bool,
+    components: Vec<u32>,
+    hardened: Vec<bool>,
+    // Indexes into `components` of the segments that would need to be hardened
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    unhardened_segments: Vec<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+/// Splits a BIP32-style path into indices and hardened flags, erroring only on
+/// malformed segments and non-Solana paths. Hardening is reported, not enforced.
+fn parse_derivation_path(path: &str) -> Result<(Vec<u32>, Vec<bool>), String> {
+    let mut segments = path.trim().split('/');
+    if segments.next() != Some("m") {
+        return Err("Path must start with \"m/\"".to_string());
+    }
+
+    let mut components = Vec::new();
+    let mut hardened = Vec::new();
+    for (i, segment) in segments.enumerate() {
+        let (digits, is_hardened) = match segment.strip_suffix('\'').or_else(|| segment.strip_suffix('h')) {
+            Some(digits) => (digits, true),
+            None => (segment, false),
+        };
+
+        let index = match digits.parse::<u32>() {
+            Ok(index) if index <= MAX_DERIVATION_INDEX && !digits.starts_with('+') => index,
+            _ => return Err(format!("Invalid path segment {}: \"{segment}\"", i + 1)),
+        };
+
+        components.push(index);
+        hardened.push(is_hardened);
+    }
+
+    if components.len() < 2 || components[..2] != [44, 501] {
+        return Err("Not a Solana path: expected m/44'/501'/...".to_string());
+    }
+    if components.len() > 4 {
+        return Err("Path is too deep: at most m/44'/501'/account'/change'".to_string());
+    }
+
+    Ok((components, hardened))
+}
+
+async fn validate_derivation_path(JsonBody(req): JsonBody<DerivationPathRequest>) -> impl IntoResponse {
+    let (components, hardened) = match parse_derivation_path(&req.path) {
+        Ok(parsed) => parsed,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    // Ed25519 (SLIP-10) only supports hardened derivation, so any plain segment
+    // makes the path unusable for Solana keys
+    let unhardened_segments: Vec<usize> = hardened
+        .iter()
+        .enumerate()
+        .filter(|(_, is_hardened)| !**is_hardened)
+        .map(|(i, _)| i)
+        .collect();
+    let reason = (!unhardened_segments.is_empty())
+        .then(|| "ed25519 keys only support hardened derivation; harden every segment".to_string());
+
+    let response = DerivationPathResponse {
+        valid: unhardened_segments.is_empty(),
+        components,
+        hardened,
+        unhardened_segments,
+        reason,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 43. INSTRUCTION DIFF ENDPOINT =====
+
+#[derive(Deserialize)]
+struct InstructionDiffRequest {
+    expected: Vec<InstructionJson>, // e.g. the approved instructions
+    actual: Vec<InstructionJson>,   // e.g. the regenerated instructions
+}
+
+#[derive(Serialize)]
+struct InstructionDiffResponse {
+    identical: bool,
+    diffs: Vec<InstructionDiff>,
+}
+
+#[derive(Serialize)]
+struct InstructionDiff {
+    index: usize,
+    status: String, // "changed", "added" or "removed"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    program_id: Option<ValueDiff>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    accounts: Vec<AccountDiff>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<DataDiff>,
+}
+
+#[derive(Serialize)]
+struct ValueDiff {
+    expected: String,
+    actual: String,
+}
+
+#[derive(Serialize)]
+struct AccountDiff {
+    position: usize,
+    expected: Option<AccountMetaJson>,
+    actual: Option<AccountMetaJson>,
+}
+
+#[derive(Serialize)]
+struct DataDiff {
+    expected_len: usize,
+    actual_len: usize,
+    first_difference: usize, // byte offset
+    differing_bytes: usize,  // bytes past the shorter length count as differing
+}
+
+fn parse_instruction_list(instructions: &[InstructionJson], side: &str) -> Result<Vec<Instruction>, String> {
+    instructions
+        .iter()
+        .enumerate()
+        .map(|(i, ix)| ix.to_instruction().map_err(|e| format!("Invalid {side} instruction at index {i}: {e}")))
+        .collect()
+}
+
+/// Field-by-field comparison of two instructions at the same position, or `None` if equal.
+fn diff_instruction(index: usize, expected: &Instruction, actual: &Instruction) -> Option<InstructionDiff> {
+    let program_id = (expected.program_id != actual.program_id).then(|| ValueDiff {
+        expected: expected.program_id.to_string(),
+        actual: actual.program_id.to_string(),
+    });
+
+    let account_count = expected.accounts.len().max(actual.accounts.len());
+    let accounts: Vec<AccountDiff> = (0..account_count)
+        .filter_map(|position| {
+            let left = expected.accounts.get(position);
+            let right = actual.accounts.get(position);
+            (left != right).then(|| AccountDiff {
+                position,
+                expected: left.map(AccountMetaJson::from),
+                actual: right.map(AccountMetaJson::from),
+            })
+        })
+        .collect();
+
+    let data = (expected.data != actual.data).then(|| {
+        let shared = expected.data.len().min(actual.data.len());
+        let mismatched = (0..shared).filter(|&i| expected.data[i] != actual.data[i]);
+        let first_difference = mismatched.clone().next().unwrap_or(shared);
+        let length_delta = expected.data.len().abs_diff(actual.data.len());
+
+        DataDiff {
+            expected_len: expected.data.len(),
+            actual_len: actual.data.len(),
+            first_difference,
+            differing_bytes: mismatched.count() + length_delta,
+        }
+    });
+
+    if program_id.is_none() && accounts.is_empty() && data.is_none() {
+        return None;
+    }
+
+    Some(InstructionDiff {
+        index,
+        status: "changed".to_string(),
+        program_id,
+        accounts,
+        data,
+    })
+}
+
+async fn diff_instructions(JsonBody(req): JsonBody<InstructionDiffRequest>) -> impl IntoResponse {
+    let expected = match parse_instruction_list(&req.expected, "expected") {
+        Ok(ixs) => ixs,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let actual = match parse_instruction_list(&req.actual, "actual") {
+        Ok(ixs) => ixs,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let count = expected.len().max(actual.len());
+    let diffs: Vec<InstructionDiff> = (0..count)
+        .filter_map(|index| match (expected.get(index), actual.get(index)) {
+            (Some(left), Some(right)) => diff_instruction(index, left, right),
+            // Extra or missing instructions are reported whole, by program id only
+            (left, right) => Some(InstructionDiff {
+                index,
+                status: if left.is_some() { "removed" } else { "added" }.to_string(),
+                program_id: Some(ValueDiff {
+                    expected: left.map(|ix| ix.program_id.to_string()).unwrap_or_default(),
+                    actual: right.map(|ix| ix.program_id.to_string()).unwrap_or_default(),
+                }),
+                accounts: vec![],
+                data: None,
+            }),
+        })
+        .collect();
+
+    let response = InstructionDiffResponse {
+        identical: diffs.is_empty(),
+        diffs,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 44. VANITY KEYPAIR ENDPOINT =====
+
+// Each extra character multiplies the expected search time by ~58
+const MAX_VANITY_CHARS: usize = 5;
+const DEFAULT_VANITY_TIMEOUT_SECS: u64 = 30;
+const MAX_VANITY_TIMEOUT_SECS: u64 = 120;
+
+#[derive(Debug, Deserialize)]
+struct VanityRequest {
+    prefix: Option<String>,
+    suffix: Option<String>,
+    timeout_secs: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct VanityResponse {
+    pubkey: String,
+    secret: String,
+    attempts: BigUint,
+    elapsed_ms: u128,
+}
+
+fn validate_vanity_pattern(field: &str, pattern: &str) -> Result<(), String> {
+    match pattern.chars().enumerate().find(|(_, c)| !BASE58_ALPHABET.contains(*c)) {
+        Some((index, c)) => Err(format!(
+            "Invalid base58 character '{c}' at index {index} of {field}; addresses never contain it"
+        )),
+        None => Ok(()),
+    }
+}
+
+async fn generate_vanity_keypair(
+    request_deadline: Option<Extension<RequestDeadline>>,
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<VanityRequest>,
+) -> impl IntoResponse {
+    let prefix = req.prefix.unwrap_or_default();
+    let suffix = req.suffix.unwrap_or_default();
+
+    if prefix.is_empty() && suffix.is_empty() {
+        return Json(ApiResponse::from(Err("Provide a prefix, a suffix, or both".into())));
+    }
+    if prefix.chars().count() + suffix.chars().count() > MAX_VANITY_CHARS {
+        return Json(ApiResponse::from(Err(format!(
+            "prefix and suffix may have at most {MAX_VANITY_CHARS} characters combined"
+        ))));
+    }
+    for (field, pattern) in [("prefix", &prefix), ("suffix", &suffix)] {
+        if let Err(e) = validate_vanity_pattern(field, pattern) {
+            return Json(ApiResponse::from(Err(e)));
+        }
+    }
+
+    let timeout_secs = req.timeout_secs.unwrap_or(DEFAULT_VANITY_TIMEOUT_SECS);
+    if timeout_secs == 0 || timeout_secs > MAX_VANITY_TIMEOUT_SECS {
+        return Json(ApiResponse::from(Err(format!(
+            "timeout_secs must be between 1 and {MAX_VANITY_TIMEOUT_SECS}"
+        ))));
+    }
+
+    let started = std::time::Instant::now();
+    // Stop the workers at the caller's deadline too; dropping the future alone would not
+    let mut deadline = started + Duration::from_secs(timeout_secs);
+    if let Some(Extension(RequestDeadline(request_deadline))) = request_deadline {
+        deadline = deadline.min(request_deadline);
+    }
+    let found = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let prefix = Arc::new(prefix);
+    let suffix = Arc::new(suffix);
+
+    // One blocking search loop per core; the first match stops the others
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let workers: Vec<_> = (0..threads)
+        .map(|_| {
+            let found = Arc::clone(&found);
+            let prefix = Arc::clone(&prefix);
+            let suffix = Arc::clone(&suffix);
+            tokio::task::spawn_blocking(move || {
+                let mut attempts = 0u64;
+                while !found.load(std::sync::atomic::Ordering::Relaxed) {
+                    // Checking the clock every iteration would dominate the loop
+                    if attempts.is_multiple_of(1024) && std::time::Instant::now() >= deadline {
+                        break;
+                    }
+                    attempts += 1;
+
+                    let keypair = Keypair::new();
+                    let address = keypair.pubkey().to_string();
+                    if address.starts_with(prefix.as_str())
+                        && address.ends_with(suffix.as_str())
+                        && !found.swap(true, std::sync::atomic::Ordering::Relaxed)
+                    {
+                        return (Some(keypair), attempts);
+                    }
+                }
+                (None, attempts)
+            })
+        })
+        .collect();
+
+    let mut attempts = 0;
+    let mut matched = None;
+    for worker in workers {
+        match worker.await {
+            Ok((keypair, count)) => {
+                attempts += count;
+                matched = matched.or(keypair);
+            }
+            Err(e) => return Json(ApiResponse::from(Err(format!("Vanity search failed: {e}")))),
+        }
+    }
+
+    let Some(keypair) = matched else {
+        return Json(ApiResponse::from(Err(format!(
+            "No matching address found within {timeout_secs}s ({attempts} attempts)"
+        ))));
+    };
+
+    let response = VanityResponse {
+        pubkey: keypair.pubkey().to_string(),
+        secret: bs58::encode(keypair.to_bytes()).into_string(),
+        attempts: bigint.wrap(attempts),
+        elapsed_ms: started.elapsed().as_millis(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 45. BATCH PDA DERIVATION ENDPOINT =====
+
+const DEFAULT_MAX_BATCH_PDAS: usize = 256;
+
+/// One PDA seed, e.g. `{ "type": "string", "value": "vault" }` or
+/// `{ "type": "pubkey", "value": "<base58>" }`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+enum SeedSpec {
+    String(String),
+    Pubkey(String),
+    Base64(String),
+    Hex(String),
+    U8(u8),
+    U64Le(u64),
+}
+
+impl SeedSpec {
+    fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        match self {
+            SeedSpec::String(value) => Ok(value.as_bytes().to_vec()),
+            SeedSpec::Pubkey(value) => Pubkey::from_str(value)
+                .map(|key| key.to_bytes().to_vec())
+                .map_err(|_| "invalid pubkey".to_string()),
+            SeedSpec::Base64(value) => base64::prelude::BASE64_STANDARD
+                .decode(value)
+                .map_err(|_| "invalid base64".to_string()),
+            SeedSpec::Hex(value) => hex::decode(value).map_err(|_| "invalid hex".to_string()),
+            SeedSpec::U8(value) => Ok(vec![*value]),
+            SeedSpec::U64Le(value) => Ok(value.to_le_bytes().to_vec()),
+        }
+    }
+}
+
+/// Decodes one seed set, leaving room for the bump seed that gets appended.
+fn seed_set_bytes(seed_set: &[SeedSpec]) -> Result<Vec<Vec<u8>>, String> {
+    // The bump seed takes up one of the MAX_SEEDS slots
+    if seed_set.len() >= solana_sdk::pubkey::MAX_SEEDS {
+        return Err(format!("at most {} seeds are allowed", solana_sdk::pubkey::MAX_SEEDS - 1));
+    }
+
+    seed_set
+        .iter()
+        .enumerate()
+        .map(|(j, seed)| match seed.to_bytes() {
+            Ok(bytes) if bytes.len() > solana_sdk::pubkey::MAX_SEED_LEN => Err(format!(
+                "seed {j}: {} bytes exceeds the {}-byte limit",
+                bytes.len(),
+                solana_sdk::pubkey::MAX_SEED_LEN
+            )),
+            Ok(bytes) => Ok(bytes),
+            Err(e) => Err(format!("seed {j}: {e}")),
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct PdaBatchRequest {
+    program_id: String,
+    seed_sets: Vec<Vec<SeedSpec>>,
+}
+
+#[derive(Serialize)]
+struct DerivedPda {
+    address: String,
+    bump: u8,
+}
+
+async fn derive_pda_batch(
+    State(state): State<Arc<AppState>>,
+    JsonBody(req): JsonBody<PdaBatchRequest>,
+) -> impl IntoResponse {
+    let max_batch = state.limits.pdas;
+    if req.seed_sets.is_empty() || req.seed_sets.len() > max_batch {
+        return Json(ApiResponse::from(Err(format!(
+            "seed_sets must contain between 1 and {max_batch} entries"
+        ))));
+    }
+
+    let program_id = match Pubkey::from_str(&req.program_id) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid program_id pubkey".into()))),
+    };
+
+    let mut pdas = Vec::with_capacity(req.seed_sets.len());
+    for (i, seed_set) in req.seed_sets.iter().enumerate() {
+        let seeds = match seed_set_bytes(seed_set) {
+            Ok(seeds) => seeds,
+            Err(e) => return Json(ApiResponse::from(Err(format!("Seed set {i}: {e}")))),
+        };
+
+        let seed_refs: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        match Pubkey::try_find_program_address(&seed_refs, &program_id) {
+            Some((address, bump)) => pdas.push(DerivedPda {
+                address: address.to_string(),
+                bump,
+            }),
+            None => {
+                return Json(ApiResponse::from(Err(format!("Seed set {i}: no valid bump found"))));
+            }
+        }
+    }
+
+    Json(ApiResponse::from(Ok(pdas)))
+}
+
+// ===== 46. PDA FROM KNOWN BUMP ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct PdaCreateRequest {
+    program_id: String,
+    seeds: Vec<SeedSpec>,
+    bump: u8,
+}
+
+#[derive(Serialize)]
+struct PdaCreateResponse {
+    address: String,
+    bump: u8,
+}
+
+async fn create_pda(JsonBody(req): JsonBody<PdaCreateRequest>) -> impl IntoResponse {
+    let program_id = match Pubkey::from_str(&req.program_id) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid program_id pubkey".into()))),
+    };
+
+    let mut seeds = match seed_set_bytes(&req.seeds) {
+        Ok(seeds) => seeds,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+    seeds.push(vec![req.bump]);
+
+    // No bump search: fails if this exact bump lands on the ed25519 curve
+    let seed_refs: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+    let address = match Pubkey::create_program_address(&seed_refs, &program_id) {
+        Ok(address) => address,
+        Err(_) => {
+            return Json(ApiResponse::from(Err(
+                "Seeds and bump produce an on-curve address, which is not a valid PDA".into(),
+            )));
+        }
+    };
+
+    let response = PdaCreateResponse {
+        address: address.to_string(),
+        bump: req.bump,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 47. TRANSACTION PREVIEW ENDPOINT =====
+
+#[derive(Deserialize)]
+struct TxPreviewRequest {
+    // Exactly one of the two
+    #[serde(default)]
+    instructions: Vec<InstructionJson>,
+    transaction: Option<String>, // base64-encoded serialized transaction
+}
+
+#[derive(Serialize)]
+struct TxPreviewResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fee_payer: Option<String>,
+    instructions: Vec<InstructionPreview>,
+}
+
+#[derive(Serialize)]
+struct InstructionPreview {
+    index: usize,
+    program_id: String,
+    recognized: bool,
+    summary: String,
+}
+
+/// Expands compiled instructions back into full `Instruction`s using the message's account keys.
+fn transaction_instructions(tx: &Transaction) -> Vec<Instruction> {
+    let message = &tx.message;
+    message
+        .instructions
+        .iter()
+        .map(|ix| Instruction {
+            program_id: *ix.program_id(&message.account_keys),
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|&i| AccountMeta {
+                    pubkey: message.account_keys[i as usize],
+                    is_signer: message.is_signer(i as usize),
+                    is_writable: message.is_writable(i as usize),
+                })
+                .collect(),
+            data: ix.data.clone(),
+        })
+        .collect()
+}
+
+/// Renders a raw amount with `decimals` places, trimming trailing zeros (1500000000, 9 -> "1.5").
+fn format_units(amount: u64, decimals: u8) -> String {
+    let decimals = decimals as usize;
+    if decimals == 0 {
+        return amount.to_string();
+    }
+
+    let digits = format!("{amount:0>width$}", width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    match fraction.trim_end_matches('0') {
+        "" => whole.to_string(),
+        fraction => format!("{whole}.{fraction}"),
+    }
+}
+
+fn summarize_system_instruction(ix: &Instruction) -> Option<String> {
+    use solana_sdk::system_instruction::SystemInstruction;
+
+    let key = |i: usize| ix.accounts.get(i).map(|meta| meta.pubkey.to_string());
+    let summary = match bincode::deserialize::<SystemInstruction>(&ix.data).ok()? {
+        SystemInstruction::Transfer { lamports } => {
+            format!("Transfer {} SOL from {} to {}", format_units(lamports, 9), key(0)?, key(1)?)
+        }
+        SystemInstruction::CreateAccount { lamports, space, owner } => format!(
+            "Create account {} with {} SOL and {space} bytes, owned by {owner}, paid by {}",
+            key(1)?,
+            format_units(lamports, 9),
+            key(0)?
+        ),
+        SystemInstruction::Assign { owner } => format!("Assign account {} to program {owner}", key(0)?),
+        SystemInstruction::Allocate { space } => format!("Allocate {space} bytes for account {}", key(0)?),
+        _ => return None,
+    };
+
+    Some(summary)
+}
+
+/// Amounts are only shown in tokens for the checked variants, which carry the
+/// mint's decimals; the rest are shown in base units.
+fn summarize_token_instruction(ix: &Instruction) -> Option<String> {
+    let key = |i: usize| ix.accounts.get(i).map(|meta| meta.pubkey.to_string());
+    let instruction = TokenInstruction::unpack(&ix.data).ok()?;
+    let summary = match instruction {
+        TokenInstruction::Transfer { amount } => {
+            format!("Transfer {amount} base units from {} to {}", key(0)?, key(1)?)
+        }
+        TokenInstruction::TransferChecked { amount, decimals } => format!(
+            "Transfer {} tokens of {} from {} to {}",
+            format_units(amount, decimals),
+            key(1)?,
+            key(0)?,
+            key(2)?
+        ),
+        TokenInstruction::MintTo { amount } => {
+            format!("Mint {amount} base units of {} to {}", key(0)?, key(1)?)
+        }
+        TokenInstruction::MintToChecked { amount, decimals } => {
+            format!("Mint {} tokens of {} to {}", format_units(amount, decimals), key(0)?, key(1)?)
+        }
+        TokenInstruction::Burn { amount } => {
+            format!("Burn {amount} base units of {} from {}", key(1)?, key(0)?)
+        }
+        TokenInstruction::BurnChecked { amount, decimals } => {
+            format!("Burn {} tokens of {} from {}", format_units(amount, decimals), key(1)?, key(0)?)
+        }
+        TokenInstruction::Approve { amount } => {
+            format!("Approve {} to spend {amount} base units from {}", key(1)?, key(0)?)
+        }
+        TokenInstruction::ApproveChecked { amount, decimals } => format!(
+            "Approve {} to spend {} tokens of {} from {}",
+            key(2)?,
+            format_units(amount, decimals),
+            key(1)?,
+            key(0)?
+        ),
+        TokenInstruction::Revoke => format!("Revoke the delegate of {}", key(0)?),
+        TokenInstruction::CloseAccount => {
+            format!("Close token account {} and send its rent to {}", key(0)?, key(1)?)
+        }
+        TokenInstruction::SetAuthority { authority_type, new_authority } => {
+            let name = authority_type_name(&authority_type);
+            match Option::<Pubkey>::from(new_authority) {
+                Some(new_authority) => format!("Set the {name} authority of {} to {new_authority}", key(0)?),
+                None => format!("Remove the {name} authority of {}", key(0)?),
+            }
+        }
+        TokenInstruction::InitializeMint { decimals, .. } | TokenInstruction::InitializeMint2 { decimals, .. } => {
+            format!("Initialize mint {} with {decimals} decimals", key(0)?)
+        }
+        TokenInstruction::FreezeAccount => format!("Freeze token account {}", key(0)?),
+        TokenInstruction::ThawAccount => format!("Thaw token account {}", key(0)?),
+        TokenInstruction::SyncNative => format!("Sync the wrapped SOL balance of {}", key(0)?),
+        other => {
+            let described = describe_token_instruction(&other, &BigintParams { bigint: BigintFormat::Number });
+            format!("Token instruction {}", described["type"].as_str().unwrap_or("unknown"))
+        }
+    };
+
+    Some(summary)
+}
+
+fn summarize_instruction(ix: &Instruction) -> Option<String> {
+    let key = |i: usize| ix.accounts.get(i).map(|meta| meta.pubkey.to_string());
+
+    if ix.program_id == solana_sdk::system_program::id() {
+        summarize_system_instruction(ix)
+    } else if ix.program_id == spl_token::id() || ix.program_id == spl_token_2022::id() {
+        // Token-2022 extension instructions fail to unpack and fall through as unknown
+        summarize_token_instruction(ix)
+    } else if ix.program_id == spl_associated_token_account::id() {
+        let qualifier = match ix.data.as_slice() {
+            [] | [0] => "",
+            [1] => " if missing",
+            _ => return None,
+        };
+        Some(format!(
+            "Create associated token account {}{qualifier} for wallet {} and mint {}, paid by {}",
+            key(1)?,
+            key(2)?,
+            key(3)?,
+            key(0)?
+        ))
+    } else if ix.program_id == spl_memo::id() {
+        std::str::from_utf8(&ix.data).ok().map(|memo| format!("Memo {memo:?}"))
+    } else if ix.program_id == solana_sdk::compute_budget::id() {
+        match ix.data.split_first()? {
+            (2, limit) => Some(format!("Set compute unit limit to {}", u32::from_le_bytes(limit.try_into().ok()?))),
+            (3, price) => Some(format!(
+                "Set compute unit price to {} micro-lamports",
+                u64::from_le_bytes(price.try_into().ok()?)
+            )),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+async fn preview_transaction(JsonBody(req): JsonBody<TxPreviewRequest>) -> impl IntoResponse {
+    let (fee_payer, instructions) = match (&req.transaction, req.instructions.is_empty()) {
+        (Some(encoded), true) => match decode_transaction(encoded) {
+            Ok(tx) => (tx.message.account_keys.first().map(Pubkey::to_string), transaction_instructions(&tx)),
+            Err(e) => return Json(ApiResponse::from(Err(e))),
+        },
+        (None, false) => match parse_instruction_list(&req.instructions, "input") {
+            Ok(ixs) => (None, ixs),
+            Err(e) => return Json(ApiResponse::from(Err(e))),
+        },
+        _ => {
+            return Json(ApiResponse::from(Err(
+                "Provide exactly one of instructions or transaction".into(),
+            )));
+        }
+    };
+
+    let instructions = instructions
+        .iter()
+        .enumerate()
+        .map(|(index, ix)| {
+            let summary = summarize_instruction(ix);
+            InstructionPreview {
+                index,
+                program_id: ix.program_id.to_string(),
+                recognized: summary.is_some(),
+                summary: summary.unwrap_or_else(|| {
+                    let program = known_program_name(&ix.program_id)
+                        .map(str::to_string)
+                        .unwrap_or_else(|| ix.program_id.to_string());
+                    let plural = if ix.accounts.len() == 1 { "" } else { "s" };
+                    format!("Call program {program} with {} account{plural}", ix.accounts.len())
+                }),
+            }
+        })
+        .collect();
+
+    let response = TxPreviewResponse {
+        fee_payer,
+        instructions,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 48. ONE-SIGNER BATCH VERIFY ENDPOINT =====
+
+const DEFAULT_MAX_ONE_SIGNER_ITEMS: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+struct OneSignerVerifyRequest {
+    pubkey: String, // base58-encoded public key shared by every item
+    items: Vec<SignedItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignedItem {
+    message: String,
+    signature: String, // base64-encoded signature
+}
+
+#[derive(Serialize)]
+struct OneSignerVerifyResponse {
+    pubkey: String,
+    all_valid: bool,
+    valid_count: usize,
+    invalid_count: usize,
+    results: Vec<SignedItemResult>,
+}
+
+#[derive(Serialize)]
+struct SignedItemResult {
+    index: usize,
+    valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+async fn verify_one_signer(
+    State(state): State<Arc<AppState>>,
+    JsonBody(req): JsonBody<OneSignerVerifyRequest>,
+) -> impl IntoResponse {
+    if req.items.is_empty() {
+        return Json(ApiResponse::from(Err("items must not be empty".into())));
+    }
+    let max_items = state.limits.verify_items;
+    if req.items.len() > max_items {
+        return Json(ApiResponse::from(Err(format!(
+            "Too many items: {}, maximum is {max_items}",
+            req.items.len()
+        ))));
+    }
+
+    let pubkey = match Pubkey::from_str(req.pubkey.trim()) {
+        Ok(pk) => pk.to_bytes(),
+        Err(_) => return Json(ApiResponse::from(Err(invalid_pubkey_error(req.pubkey.trim()).to_string()))),
+    };
+
+    // A malformed signature only fails its own item, not the batch
+    let results: Vec<SignedItemResult> = req
+        .items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let signature = base64::prelude::BASE64_STANDARD
+                .decode(&item.signature)
+                .ok()
+                .and_then(|bytes| Signature::try_from(bytes.as_slice()).ok());
+
+            match signature {
+                Some(signature) => SignedItemResult {
+                    index,
+                    valid: signature.verify(&pubkey, item.message.as_bytes()),
+                    error: None,
+                },
+                None => SignedItemResult {
+                    index,
+                    valid: false,
+                    error: Some("Invalid signature format".to_string()),
+                },
+            }
+        })
+        .collect();
+
+    let valid_count = results.iter().filter(|result| result.valid).count();
+    let response = OneSignerVerifyResponse {
+        pubkey: req.pubkey.trim().to_string(),
+        all_valid: valid_count == results.len(),
+        valid_count,
+        invalid_count: results.len() - valid_count,
+        results,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 49. RAW TRANSACTION MESSAGE BUILD ENDPOINT =====
+
+#[derive(Deserialize)]
+struct RawTxBuildRequest {
+    payer: String,
+    recent_blockhash: String,
+    instructions: Vec<InstructionJson>, // account flags are used exactly as given
+}
+
+#[derive(Serialize)]
+struct RawTxBuildResponse {
+    message: String,                    // serialized unsigned message, base64 unless ?tx_encoding=base58
+    account_keys: Vec<AccountMetaJson>, // compiled order (see compile_message_in_order), with merged flags
+    signers: Vec<String>,               // in signature order
+    size: usize,                        // full transaction size once every signer has signed
+}
+
+/// Compiles a legacy message that keeps the caller's account order. Accounts are
+/// deduplicated with their flags OR-ed together, in order of first appearance
+/// (payer first, each instruction's accounts before its program id). The format
+/// itself requires writable signers, read-only signers, writable non-signers and
+/// read-only non-signers in that order, so accounts only move between those
+/// groups; `Message::new` would also sort them by pubkey within each group.
+fn compile_message_in_order(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    recent_blockhash: Hash,
+) -> Result<Message, String> {
+    let mut keys = vec![AccountMeta::new(*payer, true)];
+    let metas = instructions.iter().flat_map(|ix| {
+        ix.accounts
+            .iter()
+            .cloned()
+            .chain(std::iter::once(AccountMeta::new_readonly(ix.program_id, false)))
+    });
+    for meta in metas {
+        match keys.iter_mut().find(|key| key.pubkey == meta.pubkey) {
+            Some(key) => {
+                key.is_signer |= meta.is_signer;
+                key.is_writable |= meta.is_writable;
+            }
+            None => keys.push(meta),
+        }
+    }
+
+    // Stable, so the caller's order survives within each group
+    keys.sort_by_key(|key| (!key.is_signer, !key.is_writable));
+
+    if keys.len() > usize::from(u8::MAX) + 1 {
+        return Err(format!("Too many accounts: {}, a legacy message holds at most 256", keys.len()));
+    }
+
+    let count = |is_signer: bool, is_writable: bool| {
+        keys.iter()
+            .filter(|key| key.is_signer == is_signer && key.is_writable == is_writable)
+            .count()
+    };
+    // 256 keys fit the u8 indexes, but 256 signers overflow the header
+    let signers = count(true, true) + count(true, false);
+    let too_many_signers = |_| format!("too many signers: {signers}, a legacy message holds at most 255");
+    let header = MessageHeader {
+        num_required_signatures: u8::try_from(signers).map_err(too_many_signers)?,
+        num_readonly_signed_accounts: u8::try_from(count(true, false)).map_err(too_many_signers)?,
+        num_readonly_unsigned_accounts: u8::try_from(count(false, false))
+            .map_err(|_| "Too many read-only accounts".to_string())?,
+    };
+
+    let account_keys: Vec<Pubkey> = keys.iter().map(|key| key.pubkey).collect();
+    let index_of = |pubkey: &Pubkey| {
+        account_keys.iter().position(|key| key == pubkey).expect("every key was collected above") as u8
+    };
+    let compiled = instructions
+        .iter()
+        .map(|ix| CompiledInstruction {
+            program_id_index: index_of(&ix.program_id),
+            accounts: ix.accounts.iter().map(|meta| index_of(&meta.pubkey)).collect(),
+            data: ix.data.clone(),
+        })
+        .collect();
+
+    Ok(Message {
+        header,
+        account_keys,
+        recent_blockhash,
+        instructions: compiled,
+    })
+}
+
+async fn build_raw_transaction(
+    Query(encoding): Query<TxEncodingParams>,
+    JsonBody(req): JsonBody<RawTxBuildRequest>,
+) -> impl IntoResponse {
+    if req.instructions.is_empty() {
+        return Json(ApiResponse::from(Err("At least one instruction is required".into())));
+    }
+
+    let payer = match Pubkey::from_str(req.payer.trim()) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid payer pubkey".into()))),
+    };
+
+    let recent_blockhash = match Hash::from_str(req.recent_blockhash.trim()) {
+        Ok(hash) => hash,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid recent_blockhash".into()))),
+    };
+
+    let instructions = match parse_instruction_list(&req.instructions, "input") {
+        Ok(ixs) => ixs,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let message = match compile_message_in_order(&instructions, &payer, recent_blockhash) {
+        Ok(message) => message,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+    let bytes = message.serialize();
+
+    let account_keys = message
+        .account_keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| {
+            AccountMetaJson::from(&AccountMeta {
+                pubkey: *key,
+                is_signer: message.is_signer(i),
+                is_writable: message.is_writable(i),
+            })
+        })
+        .collect();
+
+    let required = message.header.num_required_signatures as usize;
+    let response = RawTxBuildResponse {
+        message: encoding.tx_encoding.encode(&bytes),
+        account_keys,
+        signers: message.account_keys[..required].iter().map(Pubkey::to_string).collect(),
+        size: short_vec_len(required) + required * 64 + bytes.len(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 50. MINT TOKEN PROGRAM LOOKUP ENDPOINT =====
+
+#[cfg(feature = "rpc")]
+#[derive(Debug, Deserialize)]
+struct TokenProgramOfRequest {
+    mint: String,
+    rpc_url: Option<String>,
+    cluster: Option<String>,
+}
+
+#[cfg(feature = "rpc")]
+#[derive(Serialize)]
+struct TokenProgramOfResponse {
+    mint: String,
+    program_id: String,
+    program: &'static str, // "spl-token" or "token-2022"
+    decimals: u8,
+}
+
+#[cfg(feature = "rpc")]
+/// Token program and decimals of an on-chain mint, under either token program.
+async fn fetch_mint_info(
+    rpc_url: Option<&str>,
+    cluster: Option<&str>,
+    mint: &Pubkey,
+) -> Result<(Pubkey, &'static str, u8), String> {
+    let client = rpc_client(rpc_url, cluster)?;
+
+    let account = client
+        .get_account_with_commitment(mint, CommitmentConfig::confirmed())
+        .await
+        .map_err(|e| format!("Failed to fetch mint account: {e}"))?
+        .value
+        .ok_or_else(|| format!("Mint {mint} does not exist"))?;
+
+    let program = match known_program_name(&account.owner) {
+        Some(name @ ("spl-token" | "token-2022")) => name,
+        _ => return Err(format!("{mint} is not a token mint, it is owned by {}", account.owner)),
+    };
+
+    // The token-2022 layout is a superset of spl-token's, so one parser covers both
+    let state = spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data)
+        .map_err(|_| format!("{mint} is a {program} account but not an initialized mint"))?;
+
+    Ok((account.owner, program, state.base.decimals))
+}
+
+#[cfg(feature = "rpc")]
+async fn token_program_of(JsonBody(req): JsonBody<TokenProgramOfRequest>) -> impl IntoResponse {
+    let mint = match Pubkey::from_str(req.mint.trim()) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let (program_id, program, decimals) = match fetch_mint_info(req.rpc_url.as_deref(), req.cluster.as_deref(), &mint).await {
+        Ok(info) => info,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let response = TokenProgramOfResponse {
+        mint: mint.to_string(),
+        program_id: program_id.to_string(),
+        program,
+        decimals,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 51. TOKEN CONSOLIDATION ENDPOINT =====
+
+// Each source adds 47 bytes (its key plus a 15-byte transfer instruction); 22
+// fill a packet exactly when the owner is also the fee payer.
+const MAX_CONSOLIDATE_SOURCES: usize = 22;
+
+#[derive(Debug, Deserialize)]
+struct ConsolidateRequest {
+    sources: Vec<ConsolidateSource>,
+    destination_token_account: String,
+    owner: String, // owner of every source token account
+    // Duplicate sources are never merged: summing could exceed the account's balance
+    #[serde(default)]
+    dedupe: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConsolidateSource {
+    token_account: String,
+    amount: u64,
+}
+
+#[derive(Serialize)]
+struct ConsolidateResponse {
+    instructions: Vec<InstructionJson>,
+    total_amount: BigUint,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    combined_instructions_base64: Option<String>,
+}
+
+async fn consolidate_tokens(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<CombinedParams>,
+    Query(guard): Query<AmountGuardParams>,
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<ConsolidateRequest>,
+) -> impl IntoResponse {
+    let max_sources = state.limits.consolidate_sources;
+    if req.sources.is_empty() || req.sources.len() > max_sources {
+        return Json(ApiResponse::from(Err(format!(
+            "sources must contain between 1 and {max_sources} token accounts"
+        ))));
+    }
+
+    let destination = match Pubkey::from_str(&req.destination_token_account) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid destination_token_account pubkey".into()))),
+    };
+
+    let owner = match Pubkey::from_str(&req.owner) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid owner pubkey".into()))),
+    };
+
+    let mut seen = HashSet::new();
+    let mut total_amount: u64 = 0;
+    let mut instructions = Vec::with_capacity(req.sources.len());
+    for (i, entry) in req.sources.iter().enumerate() {
+        let source = match Pubkey::from_str(&entry.token_account) {
+            Ok(p) => p,
+            Err(_) => return Json(ApiResponse::from(Err(format!("Invalid source token_account at index {i}")))),
+        };
+        if source == destination {
+            return Json(ApiResponse::from(Err(format!(
+                "Source at index {i} is the destination token account"
+            ))));
+        }
+        if !seen.insert(source) {
+            let hint = if req.dedupe { "; dedupe does not merge sources, list each once" } else { "" };
+            return Json(ApiResponse::from(Err(format!("Duplicate source token_account at index {i}{hint}"))));
+        }
+
+        if entry.amount == 0 {
+            return Json(ApiResponse::from(Err(format!("Amount at index {i} must be greater than 0"))));
+        }
+        if let Err(e) = guard.check(entry.amount) {
+            return Json(ApiResponse::from(Err(format!("Source at index {i}: {e}"))));
+        }
+        total_amount = match total_amount.checked_add(entry.amount) {
+            Some(total) => total,
+            None => return Json(ApiResponse::from(Err("Total amount overflows u64".into()))),
+        };
+
+        match spl_transfer(&spl_token::id(), &source, &destination, &owner, &[], entry.amount) {
+            Ok(ix) => instructions.push(ix),
+            Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build transfer instruction: {e}")))),
+        }
+    }
+
+    let combined_instructions_base64 = if params.combined {
+        match combine_instructions(&instructions) {
+            Ok(combined) => Some(combined),
+            Err(e) => return Json(ApiResponse::from(Err(e))),
+        }
+    } else {
+        None
+    };
+
+    let response = ConsolidateResponse {
+        instructions: instructions.into_iter().map(InstructionJson::from).collect(),
+        total_amount: bigint.wrap(total_amount),
+        combined_instructions_base64,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 52. PUBKEY IDENTICON ENDPOINT =====
+
+const IDENTICON_GRID: usize = 5;
+const IDENTICON_CELL: usize = 10; // SVG units per cell
+
+#[derive(Debug, Deserialize)]
+struct IdenticonRequest {
+    pubkey: String,
+}
+
+#[derive(Serialize)]
+struct IdenticonResponse {
+    colors: Vec<String>, // [foreground, background] as #rrggbb
+    svg: String,
+}
+
+/// Converts HSL (hue in degrees, saturation and lightness in 0..=1) to `#rrggbb`.
+fn hsl_to_hex(hue: f64, saturation: f64, lightness: f64) -> String {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |value: f64| ((value + m) * 255.0).round() as u8;
+
+    format!("#{:02x}{:02x}{:02x}", channel(r), channel(g), channel(b))
+}
+
+/// GitHub-style blocky identicon: a horizontally mirrored 5x5 grid whose cells
+/// and hue come from the SHA-256 of the pubkey bytes, so it is stable across clients.
+async fn generate_identicon(JsonBody(req): JsonBody<IdenticonRequest>) -> impl IntoResponse {
+    let pubkey = match Pubkey::from_str(req.pubkey.trim()) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err(invalid_pubkey_error(req.pubkey.trim()).to_string()))),
+    };
+
+    let hash = Sha256::digest(pubkey.to_bytes());
+    let hue = u16::from_le_bytes([hash[0], hash[1]]) as f64 % 360.0;
+    let foreground = hsl_to_hex(hue, 0.65, 0.45);
+    let background = hsl_to_hex(hue, 0.65, 0.92);
+
+    let size = IDENTICON_GRID * IDENTICON_CELL;
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {size} {size}" width="{size}" height="{size}"><rect width="{size}" height="{size}" fill="{background}"/>"#
+    );
+
+    // Only the left half plus the middle column is random; the rest mirrors it
+    let half = IDENTICON_GRID.div_ceil(2);
+    for row in 0..IDENTICON_GRID {
+        for col in 0..half {
+            let bit = row * half + col;
+            if hash[2 + bit / 8] >> (bit % 8) & 1 == 0 {
+                continue;
+            }
+
+            let mirror = IDENTICON_GRID - 1 - col;
+            let columns = if mirror == col { 1 } else { 2 };
+            for x in [col, mirror].into_iter().take(columns) {
+                svg.push_str(&format!(
+                    r#"<rect x="{}" y="{}" width="{IDENTICON_CELL}" height="{IDENTICON_CELL}" fill="{foreground}"/>"#,
+                    x * IDENTICON_CELL,
+                    row * IDENTICON_CELL
+                ));
+            }
+        }
+    }
+    svg.push_str("</svg>");
+
+    let response = IdenticonResponse {
+        colors: vec![foreground, background],
+        svg,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 53. REQUEST DEADLINE PROPAGATION =====
+
+/// When the caller's `X-Request-Deadline` runs out, for handlers that spawn
+/// work the timeout cannot cancel.
+#[derive(Clone, Copy)]
+struct RequestDeadline(std::time::Instant);
+
+fn deadline_exceeded() -> Response {
+    (
+        StatusCode::GATEWAY_TIMEOUT,
+        Json(ApiResponse::<()>::from(Err("Request deadline exceeded".to_string()))),
+    )
+        .into_response()
+}
+
+/// Honors `X-Request-Deadline` (Unix epoch milliseconds): answers 504 right away
+/// if it has passed, otherwise gives the handler only the remaining budget.
+async fn enforce_deadline(mut request: Request, next: Next) -> Response {
+    let Some(value) = request.headers().get("x-request-deadline") else {
+        return next.run(request).await;
+    };
+
+    let Some(deadline_ms) = value.to_str().ok().and_then(|v| v.trim().parse::<u64>().ok()) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::from(Err(
+                "Invalid X-Request-Deadline header, expected Unix epoch milliseconds".to_string(),
+            ))),
+        )
+            .into_response();
+    };
+
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+    let remaining = match deadline_ms.checked_sub(now_ms) {
+        Some(ms) if ms > 0 => Duration::from_millis(ms),
+        _ => return deadline_exceeded(),
+    };
+
+    request
+        .extensions_mut()
+        .insert(RequestDeadline(std::time::Instant::now() + remaining));
+
+    match tokio::time::timeout(remaining, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => deadline_exceeded(),
+    }
+}
+
+// ===== 54. TYPED ATTESTATION ENDPOINTS =====
+
+// Domain separator, so an attestation can never double as a plain signed message
+const ATTESTATION_PREFIX: &str = "solana-attestation:v1";
+
+#[derive(Deserialize)]
+struct AttestationSignRequest {
+    secret: String,
+    schema: String, // e.g. "kyc-approval/1"; must not contain newlines
+    data: Value,
+}
+
+#[derive(Deserialize)]
+struct AttestationVerifyRequest {
+    pubkey: String,
+    schema: String,
+    data: Value,
+    signature: String, // base64-encoded signature over the digest
+}
+
+#[derive(Serialize)]
+struct AttestationSignResponse {
+    signature: String, // base64
+    public_key: String,
+    digest: String, // hex SHA-256 of canonical_message
+    canonical_message: String,
+}
+
+#[derive(Serialize)]
+struct AttestationVerifyResponse {
+    valid: bool,
+    digest: String,
+    canonical_message: String,
+}
+
+/// JSON with object keys sorted and no whitespace, so the same data always
+/// produces the same bytes regardless of how the client ordered its fields.
+fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+            let fields: Vec<String> = entries
+                .into_iter()
+                .map(|(key, value)| format!("{}:{}", Value::String(key.clone()), canonical_json(value)))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical_json).collect();
+            format!("[{}]", items.join(","))
+        }
+        scalar => scalar.to_string(),
+    }
+}
+
+/// `<prefix>\n<schema>\n<canonical data>` and its SHA-256, which is what gets signed.
+fn attestation_digest(schema: &str, data: &Value) -> Result<(String, [u8; 32]), String> {
+    let schema = schema.trim();
+    if schema.is_empty() || schema.contains('\n') {
+        return Err("schema must be non-empty and on a single line".to_string());
+    }
+
+    let canonical_message = format!("{ATTESTATION_PREFIX}\n{schema}\n{}", canonical_json(data));
+    let digest = Sha256::digest(canonical_message.as_bytes()).into();
+
+    Ok((canonical_message, digest))
+}
+
+async fn sign_attestation(JsonBody(req): JsonBody<AttestationSignRequest>) -> impl IntoResponse {
+    let keypair = match parse_keypair(&req.secret) {
+        Ok(kp) => kp,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let (canonical_message, digest) = match attestation_digest(&req.schema, &req.data) {
+        Ok(result) => result,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let signature = keypair.sign_message(&digest);
+
+    let response = AttestationSignResponse {
+        signature: base64::prelude::BASE64_STANDARD.encode(signature.as_ref()),
+        public_key: keypair.pubkey().to_string(),
+        digest: hex::encode(digest),
+        canonical_message,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+async fn verify_attestation(JsonBody(req): JsonBody<AttestationVerifyRequest>) -> impl IntoResponse {
+    let pubkey = match Pubkey::from_str(req.pubkey.trim()) {
+        Ok(pk) => pk,
+        Err(_) => return Json(ApiResponse::from(Err(invalid_pubkey_error(req.pubkey.trim()).to_string()))),
+    };
+
+    let signature = match base64::prelude::BASE64_STANDARD
+        .decode(req.signature.trim())
+        .ok()
+        .and_then(|bytes| Signature::try_from(bytes.as_slice()).ok())
+    {
+        Some(sig) => sig,
+        None => return Json(ApiResponse::from(Err("Invalid signature format".into()))),
+    };
+
+    let (canonical_message, digest) = match attestation_digest(&req.schema, &req.data) {
+        Ok(result) => result,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let response = AttestationVerifyResponse {
+        valid: signature.verify(&pubkey.to_bytes(), &digest),
+        digest: hex::encode(digest),
+        canonical_message,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 55. FEE PAYER ROTATION ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct SetFeePayerRequest {
+    transaction: String, // base64-encoded unsigned transaction
+    fee_payer: String,
+}
+
+#[derive(Serialize)]
+struct SetFeePayerResponse {
+    transaction: String,  // unsigned, base64 unless ?tx_encoding=base58
+    message: String,      // the message alone, same encoding
+    signers: Vec<String>, // required signers in signature order, fee payer first
+}
+
+async fn set_fee_payer(
+    Query(encoding): Query<TxEncodingParams>,
+    JsonBody(req): JsonBody<SetFeePayerRequest>,
+) -> impl IntoResponse {
+    let tx = match decode_transaction(&req.transaction) {
+        Ok(tx) => tx,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    // Any signature would be invalidated by the new message
+    if tx.signatures.iter().any(|sig| *sig != Signature::default()) {
+        return Json(ApiResponse::from(Err(
+            "Transaction is already signed; set the fee payer before signing".into(),
+        )));
+    }
+
+    let fee_payer = match Pubkey::from_str(req.fee_payer.trim()) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid fee_payer pubkey".into()))),
+    };
+
+    // Recompiling rather than swapping account_keys[0] keeps the keys deduplicated
+    // when the new payer already appears, and drops the old payer if nothing else uses it
+    let instructions = transaction_instructions(&tx);
+    let message = Message::new_with_blockhash(&instructions, Some(&fee_payer), &tx.message.recent_blockhash);
+    let required = message.header.num_required_signatures as usize;
+    let signers = message.account_keys[..required].iter().map(Pubkey::to_string).collect();
+    let message_bytes = message.serialize();
+
+    let transaction = match encode_transaction(&Transaction::new_unsigned(message), encoding.tx_encoding) {
+        Ok(encoded) => encoded,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let response = SetFeePayerResponse {
+        transaction,
+        message: encoding.tx_encoding.encode(&message_bytes),
+        signers,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 56. MINT SUPPLY CHECK ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct CheckSupplyRequest {
+    current_supply: u64,
+    amount: u64,
+}
+
+#[derive(Serialize)]
+struct CheckSupplyResponse {
+    would_overflow: bool,
+    new_supply: Option<BigUint>, // None when the mint would overflow
+    headroom: BigUint,           // the most that can still be minted
+}
+
+async fn check_mint_supply(
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<CheckSupplyRequest>,
+) -> impl IntoResponse {
+    let new_supply = req.current_supply.checked_add(req.amount);
+
+    let response = CheckSupplyResponse {
+        would_overflow: new_supply.is_none(),
+        new_supply: new_supply.map(|supply| bigint.wrap(supply)),
+        headroom: bigint.wrap(u64::MAX - req.current_supply),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 57. REQUIRED SIGNERS ENDPOINT =====
+
+#[derive(Deserialize)]
+struct RequiredSignersRequest {
+    instructions: Vec<InstructionJson>,
+    fee_payer: Option<String>, // always a signer; defaults to the first signer found
+}
+
+#[derive(Serialize)]
+struct RequiredSignersResponse {
+    fee_payer: Option<String>, // None when nothing needs to sign and no fee_payer was given
+    signers: Vec<String>,      // deduplicated, fee payer first, then in first-seen order
+}
+
+async fn required_signers(JsonBody(req): JsonBody<RequiredSignersRequest>) -> impl IntoResponse {
+    let instructions = match parse_instruction_list(&req.instructions, "input") {
+        Ok(ixs) => ixs,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let fee_payer = match req.fee_payer.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(value) => match Pubkey::from_str(value) {
+            Ok(p) => Some(p),
+            Err(_) => return Json(ApiResponse::from(Err("Invalid fee_payer pubkey".into()))),
+        },
+    };
+
+    let mut seen = HashSet::new();
+    let signers: Vec<Pubkey> = fee_payer
+        .into_iter()
+        .chain(
+            instructions
+                .iter()
+                .flat_map(|ix| &ix.accounts)
+                .filter(|meta| meta.is_signer)
+                .map(|meta| meta.pubkey),
+        )
+        .filter(|pubkey| seen.insert(*pubkey))
+        .collect();
+
+    let response = RequiredSignersResponse {
+        fee_payer: signers.first().map(Pubkey::to_string),
+        signers: signers.iter().map(Pubkey::to_string).collect(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 58. STRICT FIELD CHECKING =====
+
+#[derive(Debug, Deserialize)]
+struct StrictFieldsParams {
+    #[serde(default)]
+    strict_fields: bool,
+}
+
+/// Request body extractor that behaves like `Json`, except under
+/// `?strict_fields=true` fields the request type doesn't know (usually typos)
+/// are rejected with "unknown field: X" instead of being silently dropped.
+struct JsonBody<T>(T);
+
+fn strict_fields_rejection(error: String) -> Response {
+    (
+        StatusCode::UNPROCESSABLE_ENTITY,
+        Json(ApiResponse::<()>::from(Err(error))),
+    )
+        .into_response()
+}
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for JsonBody<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(params) =
+            Query::<StrictFieldsParams>::try_from_uri(request.uri()).map_err(IntoResponse::into_response)?;
+
+        if !params.strict_fields {
+            let Json(value) = Json::<T>::from_request(request, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            return Ok(JsonBody(value));
+        }
+
+        let Json(body) = Json::<Value>::from_request(request, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        let mut unknown = Vec::new();
+        let value = serde_ignored::deserialize(body, |path| unknown.push(path.to_string()))
+        .map_err(|e| strict_fields_rejection(e.to_string()))?;
+
+        if !unknown.is_empty() {
+            return Err(strict_fields_rejection(format!("unknown field: {}", unknown.join(", "))));
+        }
+
+        Ok(JsonBody(value))
+    }
+}
+
+// ===== 59. TOKEN TRANSFER PLAN ENDPOINT =====
+
+const DEFAULT_MAX_PLAN_TRANSFERS: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+struct TransferPlanRequest {
+    owner: String, // pays the fees and owns every source ATA
+    transfers: Vec<PlannedTransfer>,
+    // Sums transfers of the same mint to the same destination into one
+    #[serde(default)]
+    dedupe: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlannedTransfer {
+    destination: String, // wallet; tokens go to its associated token account
+    mint: String,
+    amount: u64,
+}
+
+#[derive(Serialize)]
+struct TransferPlanResponse {
+    groups: Vec<Vec<InstructionJson>>, // one transaction each, in request order
+    transaction_sizes: Vec<usize>,     // signed size of each group, in bytes
+}
+
+/// Signed size of a transaction holding `instructions`, paid for by `payer`.
+fn signed_transaction_size(instructions: &[Instruction], payer: &Pubkey) -> usize {
+    let message = Message::new(instructions, Some(payer));
+    let signers = message.header.num_required_signatures as usize;
+    short_vec_len(signers) + signers * 64 + message.serialize().len()
+}
+
+async fn plan_token_transfers(
+    State(state): State<Arc<AppState>>,
+    Query(guard): Query<AmountGuardParams>,
+    JsonBody(req): JsonBody<TransferPlanRequest>,
+) -> impl IntoResponse {
+    let max_transfers = state.limits.plan_transfers;
+    if req.transfers.is_empty() || req.transfers.len() > max_transfers {
+        return Json(ApiResponse::from(Err(format!(
+            "transfers must contain between 1 and {max_transfers} entries"
+        ))));
+    }
+
+    let owner = match Pubkey::from_str(&req.owner) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid owner pubkey".into()))),
+    };
+
+    // (index, destination, mint, amount), duplicates folded into their first entry
+    let mut planned: Vec<(usize, Pubkey, Pubkey, u64)> = Vec::with_capacity(req.transfers.len());
+    let mut positions: HashMap<(Pubkey, Pubkey), usize> = HashMap::new();
+    for (i, transfer) in req.transfers.iter().enumerate() {
+        let mint = match Pubkey::from_str(&transfer.mint) {
+            Ok(p) => p,
+            Err(_) => return Json(ApiResponse::from(Err(format!("Invalid mint pubkey at index {i}")))),
+        };
+        let destination = match Pubkey::from_str(&transfer.destination) {
+            Ok(p) => p,
+            Err(_) => return Json(ApiResponse::from(Err(format!("Invalid destination pubkey at index {i}")))),
+        };
+        if destination == owner {
+            return Json(ApiResponse::from(Err(format!("Destination at index {i} is the owner"))));
+        }
+
+        if transfer.amount == 0 {
+            return Json(ApiResponse::from(Err(format!("Amount at index {i} must be greater than 0"))));
+        }
+
+        if req.dedupe
+            && let Some(&at) = positions.get(&(destination, mint))
+        {
+            let (first, _, _, amount) = &mut planned[at];
+            *amount = match amount.checked_add(transfer.amount) {
+                Some(total) => total,
+                None => {
+                    return Json(ApiResponse::from(Err(format!(
+                        "Merged amount for the transfer at index {first} overflows u64"
+                    ))));
+                }
+            };
+            continue;
+        }
+        positions.insert((destination, mint), planned.len());
+        planned.push((i, destination, mint, transfer.amount));
+    }
+
+    let mut instructions = Vec::with_capacity(planned.len());
+    for (i, destination, mint, amount) in planned {
+        if let Err(e) = guard.check(amount) {
+            return Json(ApiResponse::from(Err(format!("Transfer at index {i}: {e}"))));
+        }
+
+        match spl_transfer(
+            &spl_token::id(),
+            &get_associated_token_address(&owner, &mint),
+            &get_associated_token_address(&destination, &mint),
+            &owner,
+            &[],
+            amount,
+        ) {
+            Ok(ix) => instructions.push(ix),
+            Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build transfer instruction: {e}")))),
+        }
+    }
+
+    // Greedy: keep adding to the current group until the next transfer would not fit.
+    // A lone transfer is always far below the limit.
+    let mut groups: Vec<Vec<Instruction>> = vec![];
+    let mut current: Vec<Instruction> = vec![];
+    for instruction in instructions {
+        current.push(instruction);
+        if current.len() > 1 && signed_transaction_size(&current, &owner) > PACKET_DATA_SIZE {
+            let overflow = current.pop().into_iter().collect();
+            groups.push(std::mem::replace(&mut current, overflow));
+        }
+    }
+    groups.push(current);
+
+    let response = TransferPlanResponse {
+        transaction_sizes: groups.iter().map(|group| signed_transaction_size(group, &owner)).collect(),
+        groups: groups
+            .into_iter()
+            .map(|group| group.into_iter().map(InstructionJson::from).collect())
+            .collect(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 60. KEYPAIR SELF-TEST ENDPOINT =====
+
+const SELF_TEST_NONCE: &[u8] = b"assign keypair self-test v1";
+
+#[derive(Debug, Deserialize)]
+struct KeypairSelfTestRequest {
+    secret: String,
+}
+
+#[derive(Serialize)]
+struct KeypairSelfTestResponse {
+    ok: bool,
+    pubkey: String,
+}
+
+/// Signs a fixed nonce and verifies it against the key's public half: the same
+/// path as `/message/sign` followed by `/message/verify`, in one call.
+async fn keypair_self_test(JsonBody(req): JsonBody<KeypairSelfTestRequest>) -> impl IntoResponse {
+    let keypair = match parse_keypair(&req.secret) {
+        Ok(kp) => kp,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let signature = keypair.sign_message(SELF_TEST_NONCE);
+
+    let response = KeypairSelfTestResponse {
+        ok: signature.verify(&keypair.pubkey().to_bytes(), SELF_TEST_NONCE),
+        pubkey: keypair.pubkey().to_string(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 61. MINT ACCOUNT LAYOUT ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct MintLayoutRequest {
+    mint_authority: String,
+    supply: u64,
+    decimals: u8,
+    freeze_authority: Option<String>,
+}
+
+#[derive(Serialize)]
+struct MintLayoutResponse {
+    data: String, // base64 account data, as returned by getAccountInfo
+    len: usize,
+}
+
+async fn mint_layout(JsonBody(req): JsonBody<MintLayoutRequest>) -> impl IntoResponse {
+    let mint_authority = match Pubkey::from_str(req.mint_authority.trim()) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint_authority pubkey".into()))),
+    };
+
+    let parse_optional = |value: Option<&str>, field: &str| match value.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(value) => Pubkey::from_str(value)
+            .map(Some)
+            .map_err(|_| format!("Invalid {field} pubkey")),
+    };
+
+    let freeze_authority = match parse_optional(req.freeze_authority.as_deref(), "freeze_authority") {
+        Ok(p) => p,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let mint = spl_token::state::Mint {
+        mint_authority: Some(mint_authority).into(),
+        supply: req.supply,
+        decimals: req.decimals,
+        is_initialized: true,
+        freeze_authority: freeze_authority.into(),
+    };
+
+    let mut data = vec![0u8; spl_token::state::Mint::LEN];
+    if let Err(e) = spl_token::state::Mint::pack(mint, &mut data) {
+        return Json(ApiResponse::from(Err(format!("Failed to pack mint: {e}"))));
+    }
+
+    let response = MintLayoutResponse {
+        data: base64::prelude::BASE64_STANDARD.encode(&data),
+        len: data.len(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 62. MINT ACCOUNT PARSE ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct AccountDataRequest {
+    data: String, // base64 account data, as returned by getAccountInfo
+}
+
+#[derive(Serialize)]
+struct MintParseResponse {
+    mint_authority: Option<String>,
+    supply: BigUint,
+    decimals: u8,
+    is_initialized: bool,
+    freeze_authority: Option<String>,
+}
+
+fn decode_account_data(data: &str) -> Result<Vec<u8>, String> {
+    base64::prelude::BASE64_STANDARD
+        .decode(data.trim())
+        .map_err(|_| "Invalid base64 data".to_string())
+}
+
+async fn parse_mint(
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<AccountDataRequest>,
+) -> impl IntoResponse {
+    let data = match decode_account_data(&req.data) {
+        Ok(bytes) => bytes,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    if data.len() != spl_token::state::Mint::LEN {
+        return Json(ApiResponse::from(Err(format!(
+            "Mint data must be {} bytes, got {}",
+            spl_token::state::Mint::LEN,
+            data.len()
+        ))));
+    }
+
+    // Unchecked so an allocated but uninitialized mint still decodes
+    let mint = match spl_token::state::Mint::unpack_unchecked(&data) {
+        Ok(mint) => mint,
+        Err(_) => return Json(ApiResponse::from(Err("Data is not a valid mint account".into()))),
+    };
+
+    let response = MintParseResponse {
+        mint_authority: Option::<Pubkey>::from(mint.mint_authority).map(|key| key.to_string()),
+        supply: bigint.wrap(mint.supply),
+        decimals: mint.decimals,
+        is_initialized: mint.is_initialized,
+        freeze_authority: Option::<Pubkey>::from(mint.freeze_authority).map(|key| key.to_string()),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 63. TOKEN ACCOUNT PARSE ENDPOINT =====
+
+#[derive(Serialize)]
+struct TokenAccountParseResponse {
+    mint: String,
+    owner: String,
+    amount: BigUint,
+    delegate: Option<String>,
+    state: &'static str, // "uninitialized", "initialized" or "frozen"
+    delegated_amount: BigUint,
+    close_authority: Option<String>,
+}
+
+async fn parse_token_account(
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<AccountDataRequest>,
+) -> impl IntoResponse {
+    use spl_token::state::{Account, AccountState};
+
+    let data = match decode_account_data(&req.data) {
+        Ok(bytes) => bytes,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    if data.len() != Account::LEN {
+        return Json(ApiResponse::from(Err(format!(
+            "Token account data must be {} bytes, got {}",
+            Account::LEN,
+            data.len()
+        ))));
+    }
+
+    let account = match Account::unpack_unchecked(&data) {
+        Ok(account) => account,
+        Err(_) => return Json(ApiResponse::from(Err("Data is not a valid token account".into()))),
+    };
+
+    let response = TokenAccountParseResponse {
+        mint: account.mint.to_string(),
+        owner: account.owner.to_string(),
+        amount: bigint.wrap(account.amount),
+        delegate: Option::<Pubkey>::from(account.delegate).map(|key| key.to_string()),
+        state: match account.state {
+            AccountState::Uninitialized => "uninitialized",
+            AccountState::Initialized => "initialized",
+            AccountState::Frozen => "frozen",
+        },
+        delegated_amount: bigint.wrap(account.delegated_amount),
+        close_authority: Option::<Pubkey>::from(account.close_authority).map(|key| key.to_string()),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 64. CLOSE TOKEN ACCOUNT ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct CloseTokenAccountRequest {
+    account: String,     // token account to close; must hold no tokens
+    destination: String, // receives the reclaimed rent lamports
+    owner: String,
+    // Signs instead of owner when the account has a separate close authority set
+    close_authority: Option<String>,
+    token_program: Option<String>, // "spl-token" (default), "token-2022" or a program id
+}
+
+async fn close_token_account(JsonBody(req): JsonBody<CloseTokenAccountRequest>) -> impl IntoResponse {
+    let account = match Pubkey::from_str(&req.account) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid account pubkey".into()))),
+    };
+
+    let destination = match Pubkey::from_str(&req.destination) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid destination pubkey".into()))),
+    };
+
+    let owner = match Pubkey::from_str(&req.owner) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid owner pubkey".into()))),
+    };
+
+    let authority = match req.close_authority.as_deref().map(str::trim) {
+        None | Some("") => owner,
+        Some(value) => match Pubkey::from_str(value) {
+            Ok(p) => p,
+            Err(_) => return Json(ApiResponse::from(Err("Invalid close_authority pubkey".into()))),
+        },
+    };
+
+    let token_program = match parse_token_program(req.token_program.as_deref(), spl_token::id()) {
+        Ok(p) => p,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    if account == destination {
+        return Json(ApiResponse::from(Err("Account and destination cannot be the same".into())));
+    }
+
+    // Once a close authority is set, the program only accepts its signature, not the owner's
+    let instruction = match spl_token_2022::instruction::close_account(
+        &token_program,
+        &account,
+        &destination,
+        &authority,
+        &[], // no multisig signers
+    ) {
+        Ok(ix) => ix,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to build close instruction: {e}")))),
+    };
+
+    Json(ApiResponse::from(Ok(InstructionJson::from(instruction))))
+}
+
+// ===== 65. MINT DECIMALS CHECK ENDPOINT =====
+
+#[cfg(feature = "rpc")]
+#[derive(Debug, Deserialize)]
+struct TokenDecimalsRequest {
+    mint: String,
+    rpc_url: Option<String>,
+    cluster: Option<String>,
+    assumed_decimals: Option<u8>, // what the client was about to use
+}
+
+#[cfg(feature = "rpc")]
+#[derive(Serialize)]
+struct TokenDecimalsResponse {
+    actual: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assumed: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matches: Option<bool>,
+}
+
+#[cfg(feature = "rpc")]
+async fn token_decimals(JsonBody(req): JsonBody<TokenDecimalsRequest>) -> impl IntoResponse {
+    let mint = match Pubkey::from_str(req.mint.trim()) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let (_, _, actual) = match fetch_mint_info(req.rpc_url.as_deref(), req.cluster.as_deref(), &mint).await {
+        Ok(info) => info,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let response = TokenDecimalsResponse {
+        actual,
+        assumed: req.assumed_decimals,
+        matches: req.assumed_decimals.map(|assumed| assumed == actual),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 66. TRANSACTION MESSAGE SIGNING ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct SignTxMessageRequest {
+    secret: String,
+    message: String, // base64 serialized message, legacy or v0
+}
+
+#[derive(Serialize)]
+struct SignTxMessageResponse {
+    signature: String, // base64
+    public_key: String,
+    signer_index: usize, // position of this signature in the transaction's signature list
+}
+
+async fn sign_tx_message(JsonBody(req): JsonBody<SignTxMessageRequest>) -> impl IntoResponse {
+    let keypair = match parse_keypair(&req.secret) {
+        Ok(kp) => kp,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let bytes = match base64::prelude::BASE64_STANDARD.decode(req.message.trim()) {
+        Ok(bytes) => bytes,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid base64 message".into()))),
+    };
+
+    // Refuse anything that isn't a transaction message, so this can't be used
+    // to sign arbitrary bytes with the key
+    let message = match bincode::deserialize::<VersionedMessage>(&bytes) {
+        Ok(message) if message.sanitize().is_ok() && message.serialize() == bytes => message,
+        _ => return Json(ApiResponse::from(Err("Data is not a valid transaction message".into()))),
+    };
+
+    let required = message.header().num_required_signatures as usize;
+    let signer_index = match message.static_account_keys()[..required]
+        .iter()
+        .position(|key| *key == keypair.pubkey())
+    {
+        Some(index) => index,
+        None => {
+            return Json(ApiResponse::from(Err(format!(
+                "{} is not a required signer of this message",
+                keypair.pubkey()
+            ))));
+        }
+    };
+
+    let signature = keypair.sign_message(&bytes);
+
+    let response = SignTxMessageResponse {
+        signature: base64::prelude::BASE64_STANDARD.encode(signature.as_ref()),
+        public_key: keypair.pubkey().to_string(),
+        signer_index,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 67. INSTRUCTION VALIDATION ENDPOINT =====
+
+#[derive(Serialize)]
+struct InstructionValidateResponse {
+    valid: bool,
+    issues: Vec<FieldError>, // every problem found, not just the first
+}
+
+async fn validate_instruction(JsonBody(req): JsonBody<InstructionJson>) -> impl IntoResponse {
+    let mut issues = Vec::new();
+
+    if Pubkey::from_str(&req.program_id).is_err() {
+        issues.push(FieldError::new("program_id", "Invalid program_id pubkey"));
+    }
+
+    // The same key listed as both signer and non-signer usually means a
+    // hand-built instruction copied the wrong flags
+    let mut signer_flags: Vec<(Pubkey, bool, usize)> = Vec::new();
+    for (i, meta) in req.accounts.iter().enumerate() {
+        let field = format!("accounts[{i}].pubkey");
+        let Ok(pubkey) = Pubkey::from_str(&meta.pubkey) else {
+            issues.push(FieldError::new(&field, "Invalid account pubkey"));
+            continue;
+        };
+
+        match signer_flags.iter().find(|(key, _, _)| *key == pubkey) {
+            Some((_, is_signer, first)) if *is_signer != meta.is_signer => issues.push(FieldError::new(
+                &format!("accounts[{i}].is_signer"),
+                &format!("{pubkey} is_signer is {}, but {} at accounts[{first}]", meta.is_signer, is_signer),
+            )),
+            Some(_) => {}
+            None => signer_flags.push((pubkey, meta.is_signer, i)),
+        }
+    }
+
+    if base64::prelude::BASE64_STANDARD.decode(&req.instruction_data).is_err() {
+        issues.push(FieldError::new("instruction_data", "Invalid base64 instruction_data"));
+    }
+
+    let response = InstructionValidateResponse {
+        valid: issues.is_empty(),
+        issues,
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 68. KEYPAIR STREAM ENDPOINT =====
+
+const DEFAULT_MAX_STREAM_KEYPAIRS: usize = 100_000;
+
+#[derive(Debug, Deserialize)]
+struct KeypairStreamRequest {
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct StreamedKeypair {
+    index: usize,
+    pubkey: String,
+    secret: String,
+}
+
+/// Newline-delimited JSON, one keypair per line as it is generated. The small
+/// channel keeps generation just ahead of the client instead of buffering the
+/// whole set, and a disconnected client stops the generator.
+async fn stream_keypairs(
+    State(state): State<Arc<AppState>>,
+    JsonBody(req): JsonBody<KeypairStreamRequest>,
+) -> Response {
+    let max_count = state.limits.stream_keypairs;
+    if req.count == 0 || req.count > max_count {
+        return Json(ApiResponse::<()>::from(Err(format!(
+            "count must be between 1 and {max_count}"
+        ))))
+        .into_response();
+    }
+
+    let (sender, receiver) = tokio::sync::mpsc::channel::<String>(64);
+    tokio::task::spawn_blocking(move || {
+        for index in 0..req.count {
+            let keypair = Keypair::new();
+            let line = StreamedKeypair {
+                index,
+                pubkey: keypair.pubkey().to_string(),
+                secret: bs58::encode(keypair.to_bytes()).into_string(),
+            };
+            let Ok(mut line) = serde_json::to_string(&line) else {
+                break;
+            };
+            line.push('\n');
+
+            if sender.blocking_send(line).is_err() {
+                break; // client went away
+            }
+        }
+    });
+
+    let lines = futures_util::stream::unfold(receiver, |mut receiver| async move {
+        receiver
+            .recv()
+            .await
+            .map(|line| (Ok::<_, std::convert::Infallible>(line), receiver))
+    });
+
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        axum::body::Body::from_stream(lines),
+    )
+        .into_response()
+}
+
+// ===== 69. NATIVE MINT ENDPOINT =====
+
+#[derive(Serialize)]
+struct NativeMintResponse {
+    address: String, // spl-token wrapped SOL mint
+    decimals: u8,
+    token_2022: NativeMint,
+}
+
+#[derive(Serialize)]
+struct NativeMint {
+    address: String,
+    decimals: u8,
+}
+
+async fn native_mint() -> impl IntoResponse {
+    let response = NativeMintResponse {
+        address: spl_token::native_mint::id().to_string(),
+        decimals: spl_token::native_mint::DECIMALS,
+        token_2022: NativeMint {
+            address: spl_token_2022::native_mint::id().to_string(),
+            decimals: spl_token_2022::native_mint::DECIMALS,
+        },
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 70. HASH SIGNATURE VERIFY ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct VerifyHashRequest {
+    digest: String,    // hex, exactly 32 bytes; the bytes that were signed
+    signature: String, // base64-encoded signature
+    pubkey: String,
+}
+
+#[derive(Serialize)]
+struct VerifyHashResponse {
+    valid: bool,
+    digest: String,
+    pubkey: String,
+}
+
+async fn verify_hash_signature(JsonBody(req): JsonBody<VerifyHashRequest>) -> impl IntoResponse {
+    let digest = match hex::decode(req.digest.trim().trim_start_matches("0x")) {
+        Ok(bytes) if bytes.len() == 32 => bytes,
+        Ok(bytes) => {
+            return Json(ApiResponse::from(Err(format!(
+                "digest must be exactly 32 bytes, got {}",
+                bytes.len()
+            ))));
+        }
+        Err(_) => return Json(ApiResponse::from(Err("Invalid hex digest".into()))),
+    };
+
+    let pubkey = match Pubkey::from_str(req.pubkey.trim()) {
+        Ok(pk) => pk,
+        Err(_) => return Json(ApiResponse::from(Err(invalid_pubkey_error(req.pubkey.trim()).to_string()))),
+    };
+
+    let signature = match base64::prelude::BASE64_STANDARD
+        .decode(req.signature.trim())
+        .ok()
+        .and_then(|bytes| Signature::try_from(bytes.as_slice()).ok())
+    {
+        Some(sig) => sig,
+        None => return Json(ApiResponse::from(Err("Invalid signature format".into()))),
+    };
+
+    let response = VerifyHashResponse {
+        valid: signature.verify(&pubkey.to_bytes(), &digest),
+        digest: hex::encode(&digest),
+        pubkey: pubkey.to_string(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 71. TOKEN METADATA UPDATE ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct UpdateMetadataFieldRequest {
+    metadata: String, // account holding the metadata; the mint itself for embedded metadata
+    update_authority: String,
+    field: String, // "name", "symbol", "uri" or any custom key
+    value: String,
+}
+
+fn parse_metadata_field(field: &str) -> Result<Field, String> {
+    let field = field.trim();
+    if field.is_empty() {
+        return Err("Field cannot be empty".into());
+    }
+    Ok(match field.to_ascii_lowercase().as_str() {
+        "name" => Field::Name,
+        "symbol" => Field::Symbol,
+        "uri" => Field::Uri,
+        // Custom keys are stored verbatim, so keep the caller's casing
+        _ => Field::Key(field.to_string()),
+    })
+}
+
+async fn update_metadata_field(JsonBody(req): JsonBody<UpdateMetadataFieldRequest>) -> impl IntoResponse {
+    let metadata = match Pubkey::from_str(req.metadata.trim()) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid metadata pubkey".into()))),
+    };
+
+    let update_authority = match Pubkey::from_str(req.update_authority.trim()) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid update_authority pubkey".into()))),
+    };
+
+    let field = match parse_metadata_field(&req.field) {
+        Ok(f) => f,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    // Token-2022 implements the metadata interface for metadata stored on the mint
+    let instruction = spl_token_metadata_interface::instruction::update_field(
+        &spl_token_2022::id(),
+        &metadata,
+        &update_authority,
+        field,
+        req.value,
+    );
+
+    Json(ApiResponse::from(Ok(InstructionJson::from(instruction))))
+}
+
+// ===== 72. TOKEN METADATA INIT ENDPOINT =====
+
+// The interface itself doesn't cap lengths; these match the Metaplex limits wallets
+// and explorers already truncate to, and keep the realloc'd mint account small
+const MAX_METADATA_NAME_LEN: usize = 32;
+const MAX_METADATA_SYMBOL_LEN: usize = 10;
+const MAX_METADATA_URI_LEN: usize = 200;
+
+#[derive(Debug, Deserialize)]
+struct InitMetadataRequest {
+    metadata: String, // the mint itself when using the metadata pointer extension
+    update_authority: String,
+    mint: String,
+    mint_authority: String,
+    name: String,
+    symbol: String,
+    uri: String,
+}
+
+async fn init_metadata(JsonBody(req): JsonBody<InitMetadataRequest>) -> impl IntoResponse {
+    let metadata = match Pubkey::from_str(req.metadata.trim()) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid metadata pubkey".into()))),
+    };
+
+    let update_authority = match Pubkey::from_str(req.update_authority.trim()) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid update_authority pubkey".into()))),
+    };
+
+    let mint = match Pubkey::from_str(req.mint.trim()) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint pubkey".into()))),
+    };
+
+    let mint_authority = match Pubkey::from_str(req.mint_authority.trim()) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid mint_authority pubkey".into()))),
+    };
+
+    for (name, value, max) in [
+        ("name", &req.name, MAX_METADATA_NAME_LEN),
+        ("symbol", &req.symbol, MAX_METADATA_SYMBOL_LEN),
+        ("uri", &req.uri, MAX_METADATA_URI_LEN),
+    ] {
+        if value.len() > max {
+            return Json(ApiResponse::from(Err(format!(
+                "{name} is {} bytes, exceeds maximum of {max}",
+                value.len()
+            ))));
+        }
+    }
+    if req.name.trim().is_empty() {
+        return Json(ApiResponse::from(Err("Name cannot be empty".into())));
+    }
+
+    let instruction = spl_token_metadata_interface::instruction::initialize(
+        &spl_token_2022::id(),
+        &metadata,
+        &update_authority,
+        &mint,
+        &mint_authority,
+        req.name,
+        req.symbol,
+        req.uri,
+    );
+
+    Json(ApiResponse::from(Ok(InstructionJson::from(instruction))))
+}
+
+// ===== 73. TOKEN LAUNCH COST ENDPOINT =====
+
+#[cfg(feature = "rpc")]
+#[derive(Debug, Deserialize)]
+struct LaunchCostRequest {
+    #[serde(default)]
+    extensions: Vec<String>, // Token-2022 mint extensions; none means plain spl-token
+    initial_mint_count: u32, // recipients that each get an ATA and a first mint
+    rpc_url: Option<String>,
+    cluster: Option<String>,
+}
+
+#[cfg(feature = "rpc")]
+#[derive(Serialize)]
+struct LaunchCostResponse {
+    token_program: &'static str,
+    mint_account_len: usize,
+    mint_rent: BigUint,
+    token_account_len: usize,
+    ata_rent: BigUint, // for all initial_mint_count accounts together
+    transaction_count: BigUint,
+    transaction_fees: BigUint,
+    total: BigUint,
+}
+
+#[cfg(feature = "rpc")]
+async fn launch_cost(
+    Query(bigint): Query<BigintParams>,
+    JsonBody(req): JsonBody<LaunchCostRequest>,
+) -> impl IntoResponse {
+    let mut extensions = Vec::with_capacity(req.extensions.len());
+    for name in &req.extensions {
+        match parse_mint_extension(name.trim()) {
+            Ok(extension) if extensions.contains(&extension) => {
+                return Json(ApiResponse::from(Err(format!("Duplicate mint extension: {name}"))));
+            }
+            Ok(extension) => extensions.push(extension),
+            Err(e) => return Json(ApiResponse::from(Err(e))),
+        }
+    }
+
+    let (token_program, mint_account_len, token_account_len) = if extensions.is_empty() {
+        ("spl-token", spl_token::state::Mint::LEN, spl_token::state::Account::LEN)
+    } else {
+        let mint_len =
+            match ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extensions) {
+                Ok(len) => len,
+                Err(e) => return Json(ApiResponse::from(Err(format!("Failed to compute mint size: {e}")))),
+            };
+        // Some mint extensions force a matching extension onto every token account, and
+        // the associated token program always adds ImmutableOwner to Token-2022 ATAs
+        let mut account_extensions = ExtensionType::get_required_init_account_extensions(&extensions);
+        if !account_extensions.contains(&ExtensionType::ImmutableOwner) {
+            account_extensions.push(ExtensionType::ImmutableOwner);
+        }
+        let account_len = match ExtensionType::try_calculate_account_len::<
+            spl_token_2022::state::Account,
+        >(&account_extensions)
+        {
+            Ok(len) => len,
+            Err(e) => {
+                return Json(ApiResponse::from(Err(format!("Failed to compute token account size: {e}"))));
+            }
+        };
+        ("token-2022", mint_len, account_len)
+    };
+
+    let client = match rpc_client(req.rpc_url.as_deref(), req.cluster.as_deref()) {
+        Ok(c) => c,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    // The one-signer transfer fee is the cluster's per-signature price
+    let signature_fee = match estimate_transfer_fee(&client).await {
+        Ok(fee) => fee,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let rent = Rent::default();
+    let mint_rent = rent.minimum_balance(mint_account_len);
+
+    // One transaction creating the mint (payer + mint keypair), then one create-ATA
+    // plus mint-to per recipient signed by the payer alone
+    let transaction_count = 1 + u64::from(req.initial_mint_count);
+
+    // initial_mint_count is caller-controlled, so every step is checked
+    let amounts = rent
+        .minimum_balance(token_account_len)
+        .checked_mul(u64::from(req.initial_mint_count))
+        .zip(signature_fee.checked_mul(transaction_count + 1))
+        .and_then(|(ata_rent, fees)| {
+            let total = mint_rent.checked_add(ata_rent)?.checked_add(fees)?;
+            Some((ata_rent, fees, total))
+        });
+    let Some((ata_rent, transaction_fees, total)) = amounts else {
+        return Json(ApiResponse::from(Err("Launch cost overflows u64 lamports".into())));
+    };
+
+    let response = LaunchCostResponse {
+        token_program,
+        mint_account_len,
+        mint_rent: bigint.wrap(mint_rent),
+        token_account_len,
+        ata_rent: bigint.wrap(ata_rent),
+        transaction_count: bigint.wrap(transaction_count),
+        transaction_fees: bigint.wrap(transaction_fees),
+        total: bigint.wrap(total),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 74. KEYPAIR FROM CLI FORMAT ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct CliKeypairRequest {
+    value: String, // JSON byte array (as in id.json) or base58 secret
+}
+
+/// Rejects the Solana CLI keypair URL forms that point at a key source
+/// instead of containing the key, so they never reach the parser.
+fn reject_cli_key_source(value: &str) -> Result<(), String> {
+    let lower = value.to_ascii_lowercase();
+    if lower.starts_with("usb:") {
+        return Err("hardware wallet key sources are not supported".into());
+    }
+
+    // A bare path is how the CLI spells file:, and ASK is the legacy prompt: form
+    let is_source = lower.starts_with("file:")
+        || lower.starts_with("prompt:")
+        || lower == "stdin"
+        || value == "ASK"
+        || value.starts_with(['/', '~', '.'])
+        || lower.ends_with(".json");
+    if is_source {
+        return Err("filesystem key sources are not supported".into());
+    }
+
+    Ok(())
+}
+
+async fn keypair_from_cli_format(JsonBody(req): JsonBody<CliKeypairRequest>) -> impl IntoResponse {
+    let value = req.value.trim();
+    if let Err(e) = reject_cli_key_source(value) {
+        return Json(ApiResponse::from(Err(e)));
+    }
+
+    let keypair = match parse_keypair(value) {
+        Ok(kp) => kp,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let response = KeypairResponse {
+        pubkey: keypair.pubkey().to_string(),
+        secret: bs58::encode(keypair.to_bytes()).into_string(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 75. SERVER-SIDE MULTI-SIGNER TRANSACTION ENDPOINT =====
+
+/// Whether `/tx/sign-multi` may sign with caller-supplied secrets, from
+/// `ALLOW_SERVER_SIGNING`. Meant for test environments only; off by default.
+fn server_signing_allowed() -> bool {
+    static ALLOWED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *ALLOWED.get_or_init(|| {
+        std::env::var("ALLOW_SERVER_SIGNING")
+            .map(|value| value.parse().expect("ALLOW_SERVER_SIGNING must be true or false"))
+            .unwrap_or(false)
+    })
+}
+
+#[derive(Deserialize)]
+struct SignMultiRequest {
+    payer: String,
+    instructions: Vec<InstructionJson>,
+    recent_blockhash: String,
+    secrets: Vec<String>, // one per required signer, payer included
+}
+
+#[derive(Serialize)]
+struct SignMultiResponse {
+    transaction: String,
+    signature: String, // the payer's, which identifies the transaction
+    signers: Vec<String>,
+}
+
+async fn sign_multi_transaction(
+    Query(encoding): Query<TxEncodingParams>,
+    JsonBody(req): JsonBody<SignMultiRequest>,
+) -> impl IntoResponse {
+    if !server_signing_allowed() {
+        return Json(ApiResponse::from(Err(
+            "Server-side signing is disabled; set ALLOW_SERVER_SIGNING=true to enable it".into(),
+        )));
+    }
+
+    if req.instructions.is_empty() {
+        return Json(ApiResponse::from(Err("At least one instruction is required".into())));
+    }
+
+    let payer = match Pubkey::from_str(req.payer.trim()) {
+        Ok(p) => p,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid payer pubkey".into()))),
+    };
+
+    let recent_blockhash = match Hash::from_str(req.recent_blockhash.trim()) {
+        Ok(hash) => hash,
+        Err(_) => return Json(ApiResponse::from(Err("Invalid recent_blockhash".into()))),
+    };
+
+    let instructions = match parse_instruction_list(&req.instructions, "input") {
+        Ok(ixs) => ixs,
+        Err(e) => return Json(ApiResponse::from(Err(e))),
+    };
+
+    let mut keypairs = Vec::with_capacity(req.secrets.len());
+    for (i, secret) in req.secrets.iter().enumerate() {
+        match parse_keypair(secret) {
+            Ok(kp) => keypairs.push(kp),
+            Err(e) => return Json(ApiResponse::from(Err(format!("{e} at index {i}")))),
+        }
+    }
+
+    let message = Message::new_with_blockhash(&instructions, Some(&payer), &recent_blockhash);
+    let required = message.header.num_required_signatures as usize;
+
+    // An extra key would make try_partial_sign fail with a less helpful error
+    for keypair in &keypairs {
+        if !message.account_keys[..required].contains(&keypair.pubkey()) {
+            return Json(ApiResponse::from(Err(format!(
+                "{} is not a required signer of this transaction",
+                keypair.pubkey()
+            ))));
+        }
+    }
+
+    let mut tx = Transaction::new_unsigned(message);
+    let signers: Vec<&Keypair> = keypairs.iter().collect();
+    if let Err(e) = tx.try_partial_sign(&signers, recent_blockhash) {
+        return Json(ApiResponse::from(Err(format!("Failed to sign transaction: {e}"))));
+    }
+
+    if !tx.is_signed() {
+        let missing: Vec<String> = tx.message.account_keys[..required]
+            .iter()
+            .zip(&tx.signatures)
+            .filter(|(_, sig)| **sig == Signature::default())
+            .map(|(key, _)| key.to_string())
+            .collect();
+        return Json(ApiResponse::from(Err(format!("Missing secrets for signers: {}", missing.join(", ")))));
+    }
+
+    let bytes = match bincode::serialize(&tx) {
+        Ok(bytes) => bytes,
+        Err(e) => return Json(ApiResponse::from(Err(format!("Failed to serialize transaction: {e}")))),
+    };
+    if bytes.len() > PACKET_DATA_SIZE {
+        return Json(ApiResponse::from(Err(format!(
+            "Transaction is {} bytes, exceeds the {PACKET_DATA_SIZE} byte limit",
+            bytes.len()
+        ))));
+    }
+
+    let response = SignMultiResponse {
+        transaction: encoding.tx_encoding.encode(&bytes),
+        signature: tx.signatures[0].to_string(),
+        signers: tx.message.account_keys[..required].iter().map(Pubkey::to_string).collect(),
+    };
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+// ===== 76. CANONICAL SIGNATURE VERIFY ENDPOINT =====
+
+#[derive(Debug, Deserialize)]
+struct VerifyCanonicalRequest {
+    message: String,
+    signature: String, // base64-encoded signature
+    pubkey: String,
+}
+
+#[derive(Serialize)]
+struct VerifyCanonicalResponse {
+    valid: bool,
+    canonical: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+/// Why a compressed point is unusable in a non-malleable signature, if it is.
+fn point_encoding_issue(bytes: &[u8; 32]) -> Option<&'static str> {
+    let compressed = curve25519_dalek::edwards::CompressedEdwardsY(*bytes);
+    match compressed.decompress() {
+        None => Some("is not a point on the curve"),
+        // Some y coordinates have a second encoding at or above the field prime
+        Some(point) if point.compress() != compressed => Some("is not canonically encoded"),
+        Some(point) if point.is_small_order() => Some("is a small-order point"),
+        Some(_) => None,
+    }
+}
+
+/// The ed25519 equation [S]B = R + [k]A with S taken mod L and no encoding
+/// checks, i.e. what a permissive verifier elsewhere would accept.
+fn verifies_leniently(r_bytes: &[u8; 32], s_bytes: &[u8; 32], pubkey: &[u8; 32], message: &[u8]) -> bool {
+    use curve25519_dalek::{edwards::EdwardsPoint, scalar::Scalar};
+
+    let Some(a) = curve25519_dalek::edwards::CompressedEdwardsY(*pubkey).decompress() else {
+        return false;
+    };
+    let hash: [u8; 64] = sha2::Sha512::new()
+        .chain_update(r_bytes)
+        .chain_update(pubkey)
+        .chain_update(message)
+        .finalize()
+        .into();
+    let k = Scalar::from_bytes_mod_order_wide(&hash);
+    let s = Scalar::from_bytes_mod_order(*s_bytes);
+
+    EdwardsPoint::vartime_double_scalar_mul_basepoint(&k, &-a, &s).compress().as_bytes() == r_bytes
+}
+
+/// Verifies `signature` and checks that neither it nor the key has another
+/// valid encoding: S reduced below the group order, R and the key canonical
+/// and not of small order.
+fn check_canonical_signature(signature: &Signature, pubkey: &Pubkey, message: &[u8]) -> VerifyCanonicalResponse {
+    let sig_bytes: [u8; 64] = (*signature).into();
+    let r_bytes: [u8; 32] = sig_bytes[..32].try_into().expect("32-byte R");
+    let s_bytes: [u8; 32] = sig_bytes[32..].try_into().expect("32-byte S");
+
+    let issue = if curve25519_dalek::scalar::Scalar::from_canonical_bytes(s_bytes).is_none() {
+        Some("S is not reduced modulo the group order".to_string())
+    } else if let Some(issue) = point_encoding_issue(&r_bytes) {
+        Some(format!("R {issue}"))
+    } else {
+        point_encoding_issue(&pubkey.to_bytes()).map(|issue| format!("public key {issue}"))
+    };
+
+    // Solana's own check is verify_strict, so it already fails on every issue above
+    let canonical = issue.is_none();
+    let valid = canonical && signature.verify(&pubkey.to_bytes(), message);
+
+    let reason = match issue {
+        // A lenient check tells a malleable-but-correct signature apart from a wrong one
+        Some(issue) => {
+            if verifies_leniently(&r_bytes, &s_bytes, &pubkey.to_bytes(), message) {
+                Some(format!("signature verifies but is non-canonical: {issue}"))
+            } else {
+                Some(format!("non-canonical signature: {issue}"))
+            }
+        }
+        None if !valid => Some("signature does not match message and public key".to_string()),
+        None => None,
+    };
+
+    VerifyCanonicalResponse {
+        valid,
+        canonical,
+        reason,
+    }
+}
+
+async fn verify_canonical_signature(JsonBody(req): JsonBody<VerifyCanonicalRequest>) -> impl IntoResponse {
+    let pubkey = match Pubkey::from_str(req.pubkey.trim()) {
+        Ok(pk) => pk,
+        Err(_) => return Json(ApiResponse::from(Err(invalid_pubkey_error(req.pubkey.trim()).to_string()))),
+    };
+
+    let signature = match base64::prelude::BASE64_STANDARD
+        .decode(req.signature.trim())
+        .ok()
+        .and_then(|bytes| Signature::try_from(bytes.as_slice()).ok())
+    {
+        Some(sig) => sig,
+        None => return Json(ApiResponse::from(Err("Invalid signature format".into()))),
+    };
+
+    let response = check_canonical_signature(&signature, &pubkey, req.message.as_bytes());
+
+    Json(ApiResponse::from(Ok(response)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spl_token::{instruction::TokenInstruction, solana_program::program_option::COption};
+
+    async fn response_data<T: DeserializeOwned>(response: impl IntoResponse) -> T {
+        let body = axum::body::to_bytes(response.into_response().into_body(), usize::MAX)
+            .await
+            .expect("response body");
+        let mut value: Value = serde_json::from_slice(&body).expect("JSON response");
+        assert_eq!(value["success"], true, "unexpected error response: {value}");
+        serde_json::from_value(value["data"].take()).expect("response data")
+    }
+
+    async fn built_set_authority(
+        target: &Pubkey,
+        authority_type: &str,
+        current_authority: &Pubkey,
+        new_authority: Option<&Pubkey>,
+    ) -> Instruction {
+        let req = SetAuthorityRequest {
+            target: target.to_string(),
+            authority_type: authority_type.to_string(),
+            current_authority: current_authority.to_string(),
+            new_authority: new_authority.map(Pubkey::to_string),
+        };
+        let ix: InstructionJson = response_data(set_token_authority(JsonBody(req)).await).await;
+        ix.to_instruction().expect("valid instruction")
+    }
+
+    #[tokio::test]
+    async fn set_authority_transfers_account_ownership() {
+        let account = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let new_owner = Pubkey::new_unique();
+
+        let ix = built_set_authority(&account, "account_owner", &owner, Some(&new_owner)).await;
+
+        assert_eq!(ix.program_id, spl_token::id());
+        assert_eq!(
+            ix.accounts,
+            vec![AccountMeta::new(account, false), AccountMeta::new_readonly(owner, true)]
+        );
+        match TokenInstruction::unpack(&ix.data).expect("token instruction") {
+            TokenInstruction::SetAuthority { authority_type, new_authority } => {
+                assert_eq!(authority_type, AuthorityType::AccountOwner);
+                assert_eq!(new_authority, COption::Some(new_owner));
+            }
+            other => panic!("expected SetAuthority, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn set_authority_disables_close_authority() {
+        let account = Pubkey::new_unique();
+        let close_authority = Pubkey::new_unique();
+
+        let ix = built_set_authority(&account, "close_account", &close_authority, None).await;
+
+        assert_eq!(ix.program_id, spl_token::id());
+        assert_eq!(
+            ix.accounts,
+            vec![AccountMeta::new(account, false), AccountMeta::new_readonly(close_authority, true)]
+        );
+        match TokenInstruction::unpack(&ix.data).expect("token instruction") {
+            TokenInstruction::SetAuthority { authority_type, new_authority } => {
+                assert_eq!(authority_type, AuthorityType::CloseAccount);
+                assert_eq!(new_authority, COption::None);
+            }
+            other => panic!("expected SetAuthority, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn raw_message_keeps_caller_account_order() {
+        let payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let mut writable = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        // Descending, so a pubkey sort would reverse them
+        writable.sort_by(|a, b| b.cmp(a));
+        let signer = Pubkey::new_unique();
+
+        let mut accounts: Vec<AccountMeta> = writable.iter().map(|key| AccountMeta::new(*key, false)).collect();
+        accounts.push(AccountMeta::new_readonly(signer, true));
+        let ix = Instruction::new_with_bytes(program, &[1, 2, 3], accounts);
+
+        let message = compile_message_in_order(std::slice::from_ref(&ix), &payer, Hash::default())
+            .expect("compiles");
+
+        assert_eq!(
+            message.account_keys,
+            [vec![payer, signer], writable.to_vec(), vec![program]].concat()
+        );
+        let reference = Message::new_with_blockhash(&[ix], Some(&payer), &Hash::default());
+        assert_eq!(message.header, reference.header);
+        assert!(message.sanitize().is_ok());
+        assert_eq!(message.instructions[0].accounts, vec![2, 3, 4, 1]);
+        assert_eq!(message.instructions[0].program_id_index, 5);
+    }
+
+    #[test]
+    fn raw_message_rejects_256_signers() {
+        let payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        // The program signing too makes 256 keys, every one of them a signer
+        let mut accounts: Vec<AccountMeta> = (0..254).map(|_| AccountMeta::new(Pubkey::new_unique(), true)).collect();
+        accounts.push(AccountMeta::new_readonly(program, true));
+        let ix = Instruction::new_with_bytes(program, &[], accounts);
+
+        let err = compile_message_in_order(&[ix], &payer, Hash::default()).expect_err("256 signers");
+        assert!(err.starts_with("too many signers"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn strict_fields_rejects_unknown_siws_field() {
+        let body = json!({
+            "pubkey": Pubkey::new_unique().to_string(),
+            "signature": "",
+            "domain": "example.com",
+            "nonce": "abcdefgh",
+            "issued_at": "2024-01-01T00:00:00Z",
+            "isued_at": "typo",
+        });
+        let request = Request::builder()
+            .method("POST")
+            .uri("/message/verify/siws?strict_fields=true")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .expect("request");
+
+        let rejection = match JsonBody::<SiwsVerifyRequest>::from_request(request, &()).await {
+            Ok(_) => panic!("unknown field was accepted"),
+            Err(rejection) => rejection,
+        };
+
+        assert_eq!(rejection.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = axum::body::to_bytes(rejection.into_body(), usize::MAX).await.expect("body");
+        let value: Value = serde_json::from_slice(&body).expect("JSON rejection");
+        assert_eq!(value["error"], "unknown field: isued_at");
+    }
+
+    async fn strict_instruction_diff(body: Value) -> Result<InstructionDiffRequest, Response> {
+        let request = Request::builder()
+            .method("POST")
+            .uri("/instruction/diff?strict_fields=true")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .expect("request");
+
+        JsonBody::<InstructionDiffRequest>::from_request(request, &()).await.map(|JsonBody(req)| req)
+    }
+
+    #[tokio::test]
+    async fn strict_fields_only_tolerates_output_fields_where_they_belong() {
+        let returned = serde_json::to_value(InstructionJson::from(transfer(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            1,
+        )))
+        .expect("instruction JSON");
+        assert!(returned["accounts"][0].get("is_sysvar").is_some());
+
+        let echoed = json!({ "expected": [returned.clone()], "actual": [returned.clone()] });
+        assert!(strict_instruction_diff(echoed).await.is_ok());
+
+        let misplaced = json!({ "expected": [returned.clone()], "actual": [returned], "is_program": true });
+        let rejection = match strict_instruction_diff(misplaced).await {
+            Ok(_) => panic!("output-only field outside an instruction was accepted"),
+            Err(rejection) => rejection,
+        };
+        let body = axum::body::to_bytes(rejection.into_body(), usize::MAX).await.expect("body");
+        let value: Value = serde_json::from_slice(&body).expect("JSON rejection");
+        assert_eq!(value["error"], "unknown field: is_program");
+    }
+
+    async fn error_message(response: Response) -> String {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.expect("body");
+        let value: Value = serde_json::from_slice(&body).expect("JSON error");
+        assert_eq!(value["success"], false, "unexpected success: {value}");
+        value["error"].as_str().expect("error message").to_string()
+    }
+
+    fn auth_router(authorized: &Pubkey) -> Router {
+        Router::new()
+            .route("/keypair", post(generate_keypair))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(HashSet::from([*authorized])),
+                require_bearer_auth,
+            ))
+    }
+
+    fn signed_auth_token(keypair: &Keypair, exp: u64) -> String {
+        let claims = AuthClaims {
+            pubkey: keypair.pubkey().to_string(),
+            exp,
+        };
+        let payload = serde_json::to_vec(&claims).expect("claims");
+        let signature = keypair.sign_message(&payload);
+        format!(
+            "{}.{}",
+            base64::prelude::BASE64_URL_SAFE_NO_PAD.encode(&payload),
+            base64::prelude::BASE64_URL_SAFE_NO_PAD.encode(signature.as_ref())
+        )
+    }
+
+    fn post_request(uri: &str, authorization: Option<&str>) -> Request {
+        let mut builder = Request::builder().method("POST").uri(uri);
+        if let Some(value) = authorization {
+            builder = builder.header(header::AUTHORIZATION, value);
+        }
+        builder.body(axum::body::Body::empty()).expect("request")
+    }
+
+    #[tokio::test]
+    async fn bearer_auth_rejects_post_without_token() {
+        use tower::ServiceExt;
+
+        let keypair = Keypair::new();
+        let response = auth_router(&keypair.pubkey())
+            .oneshot(post_request("/keypair", None))
+            .await
+            .expect("infallible");
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(error_message(response).await, "Missing bearer token");
+    }
+
+    #[tokio::test]
+    async fn bearer_auth_rejects_expired_token() {
+        use tower::ServiceExt;
+
+        let keypair = Keypair::new();
+        let router = auth_router(&keypair.pubkey());
+
+        let valid = format!("Bearer {}", signed_auth_token(&keypair, unix_now() + 60));
+        let response = router
+            .clone()
+            .oneshot(post_request("/keypair", Some(&valid)))
+            .await
+            .expect("infallible");
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let expired = format!("Bearer {}", signed_auth_token(&keypair, unix_now() - 1));
+        let response = router
+            .oneshot(post_request("/keypair", Some(&expired)))
+            .await
+            .expect("infallible");
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(error_message(response).await, "Token expired");
+    }
+
+    #[test]
+    fn canonical_check_rejects_s_plus_group_order() {
+        let keypair = Keypair::new();
+        let message = b"canonical";
+        let signature = keypair.sign_message(message);
+        assert!(check_canonical_signature(&signature, &keypair.pubkey(), message).canonical);
+
+        // S + L is the same scalar mod L, so only the encoding check can catch it
+        let mut bytes: [u8; 64] = signature.into();
+        let order = curve25519_dalek::constants::BASEPOINT_ORDER.to_bytes();
+        let mut carry = 0u16;
+        for (s, l) in bytes[32..].iter_mut().zip(order) {
+            let sum = u16::from(*s) + u16::from(l) + carry;
+            *s = sum as u8;
+            carry = sum >> 8;
+        }
+        let malleated = Signature::from(bytes);
+
+        let check = check_canonical_signature(&malleated, &keypair.pubkey(), message);
+        assert!(!check.canonical);
+        assert!(!check.valid);
+        assert_eq!(
+            check.reason.as_deref(),
+            Some("signature verifies but is non-canonical: S is not reduced modulo the group order")
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_request_deadline_times_out() {
+        use tower::ServiceExt;
+
+        let router = Router::new()
+            .route("/keypair", post(generate_keypair))
+            .route_layer(axum::middleware::from_fn(enforce_deadline));
+        let request = Request::builder()
+            .method("POST")
+            .uri("/keypair")
+            .header("x-request-deadline", (unix_now() * 1000 - 1).to_string())
+            .body(axum::body::Body::empty())
+            .expect("request");
+
+        let response = router.oneshot(request).await.expect("infallible");
+
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(error_message(response).await, "Request deadline exceeded");
+    }
+}